@@ -1,32 +1,61 @@
+use crate::document;
 use crate::memos::Memos;
 use crate::memospot::Memospot;
-use crate::migration::MigrationExt;
+use crate::migration::{CONFIG_VERSION, MigrationExt, Migrator};
+use crate::system::SystemConfig;
 
 use anyhow::{Error, Result, bail};
-use figment::providers::{Env, Format, Json, Serialized};
+use figment::providers::{Data, Env, Format, Json, Serialized};
+use figment::value::Dict;
 use figment::{Figment, Profile};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_saphyr::figment::Yaml;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use uuid::Uuid;
 
-#[derive(TS, Default, Debug, PartialEq, Clone, Deserialize, Serialize)]
+/// Memospot configuration file, `memospot.yaml`.
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct Config {
+    /// Configuration format version, used to migrate files from previous versions.
+    #[schemars(extend("x-restart" = false))]
+    pub config_version: Option<u32>,
     pub memos: Memos,
     pub memospot: Memospot,
 }
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            config_version: Some(CONFIG_VERSION),
+            memos: Memos::default(),
+            memospot: Memospot::default(),
+        }
+    }
+}
 
 #[cfg(debug_assertions)]
 pub const CONFIG_PROFILE: &str = "debug";
 #[cfg(not(debug_assertions))]
 pub const CONFIG_PROFILE: &str = "release";
 
+/// Prefix of the environment variables overriding the configuration.
+pub(crate) const ENV_PREFIX: &str = "MEMOSPOT_";
+
+/// Environment variables overriding the configuration.
+///
+/// Nested keys are separated by `__`, like `MEMOSPOT_MEMOS__PORT`.
+pub(crate) fn env_provider() -> Env {
+    Env::prefixed(ENV_PREFIX).split("__")
+}
+
 impl Config {
-    const CONFIG_HEADER: &'static str = r#"#
-#! User comments on this file will be lost whenever
-#    the configuration is updated by Memospot !
+    /// File name of the configuration JSON Schema, at the root of the `config` crate.
+    pub const SCHEMA_FILE: &'static str = "memospot.schema.json";
+
+    const CONFIG_HEADER: &'static str = r#"# yaml-language-server: $schema=https://raw.githubusercontent.com/memospot/memospot/main/crates/config/memospot.schema.json
 #
 # For an explained configuration file, see:
 # https://memospot.github.io/configuration
@@ -41,27 +70,59 @@ impl Config {
         Ok(serde_saphyr::to_string(&self)?)
     }
 
-    /// Initialize configuration from a file.
+    /// JSON Schema of the configuration file, for editors with a YAML language server.
+    ///
+    /// Descriptions come from the doc comments, and defaults from the `Default` impls.
+    pub fn json_schema() -> Result<String, Error> {
+        let schema = schemars::schema_for!(Config);
+        Ok(serde_json::to_string_pretty(&schema)? + "\n")
+    }
+
+    /// Initialize configuration from a file, along with the system-wide configuration.
     pub fn init(cfg_path: &Path) -> Result<Config, Error> {
+        Self::init_layered(cfg_path, &SystemConfig::locate())
+    }
+
+    /// Initialize configuration from a file, along with the system-wide configuration
+    /// in `system`.
+    pub fn init_layered(cfg_path: &Path, system: &SystemConfig) -> Result<Config, Error> {
+        Self::extract_layered(Yaml::file(cfg_path), system)
+    }
+
+    /// Extract the configuration from the user's `file`, along with the system-wide
+    /// configuration in `system`.
+    fn extract_layered(file: Data<Yaml>, system: &SystemConfig) -> Result<Config, Error> {
         let default_config = Config::default();
 
-        let figment = Figment::new()
-            .merge(Yaml::file(cfg_path))
-            .merge(Env::prefixed("MEMOSPOT_"))
-            .migrate()
-            .select(Profile::from_env_or("MEMOSPOT_PROFILE", CONFIG_PROFILE))
-            .join(Serialized::defaults(default_config));
+        let figment =
+            Self::file_layers(file, system).join(Serialized::defaults(default_config));
 
         Ok(figment.extract::<Config>()?)
     }
 
+    /// Configuration layers, without the built-in defaults.
+    pub(crate) fn layers(cfg_path: &Path, system: &SystemConfig) -> Figment {
+        Self::file_layers(Yaml::file(cfg_path), system)
+    }
+
+    /// Configuration layers over the user's `file`, without the built-in defaults.
+    fn file_layers(file: Data<Yaml>, system: &SystemConfig) -> Figment {
+        Figment::new()
+            .merge(Yaml::file(system.defaults_file()))
+            .merge(file)
+            .merge(env_provider())
+            .merge(Yaml::file(system.policy_file()))
+            .migrate()
+            .select(Profile::from_env_or("MEMOSPOT_PROFILE", CONFIG_PROFILE))
+    }
+
     /// Load configuration from a JSON string.
     pub fn from_json(json: &str) -> Result<Config, Error> {
         let default_config = Config::default();
 
         let figment = Figment::new()
             .merge(Json::string(json))
-            .merge(Env::prefixed("MEMOSPOT_"))
+            .merge(env_provider())
             .select(Profile::from_env_or("MEMOSPOT_PROFILE", CONFIG_PROFILE))
             .join(Serialized::defaults(default_config));
 
@@ -88,6 +149,87 @@ impl Config {
         };
 
         let file_contents = Self::CONFIG_HEADER.to_string() + &yaml;
+        Self::write_file(file_path, &file_contents).await
+    }
+
+    /// Save the changes from `previous` to a file, keeping comments, key order and unknown
+    /// keys intact.
+    ///
+    /// Saves the whole configuration if the file doesn't exist or can't be updated in
+    /// place, leaving out the values from the system-wide configuration.
+    pub async fn update_file(&self, previous: &Config, file_path: &Path) -> Result<()> {
+        self.update_file_layered(previous, file_path, &SystemConfig::locate())
+            .await
+    }
+
+    /// Save the changes from `previous` to a file, along with the system-wide configuration
+    /// in `system`. See [`update_file`](Self::update_file).
+    pub async fn update_file_layered(
+        &self,
+        previous: &Config,
+        file_path: &Path,
+        system: &SystemConfig,
+    ) -> Result<()> {
+        if file_path.is_dir() {
+            bail!("provided configuration file is a directory");
+        }
+        let Ok(yaml) = tokio::fs::read_to_string(file_path).await else {
+            return self.save_user_values(file_path, system).await;
+        };
+
+        let previous = serde_json::to_value(previous)?;
+        let current = serde_json::to_value(self)?;
+        match document::update(&yaml, &previous, &current) {
+            Ok(updated) if updated == yaml => Ok(()),
+            // The edited document must load back to this configuration as a whole.
+            Ok(updated)
+                if Self::extract_layered(Yaml::string(&updated), system)
+                    .is_ok_and(|config| config == *self) =>
+            {
+                Self::write_file(file_path, &updated).await
+            }
+            _ => self.save_user_values(file_path, system).await,
+        }
+    }
+
+    /// Save to a file, leaving out the values set by the system-wide configuration in
+    /// `system`, so it keeps applying when changed.
+    ///
+    /// Policy values are always left out. System-wide defaults are left out unless changed.
+    async fn save_user_values(&self, file_path: &Path, system: &SystemConfig) -> Result<()> {
+        let system_defaults: Config = Figment::new()
+            .merge(Yaml::file(system.defaults_file()))
+            .join(Serialized::defaults(Config::default()))
+            .extract()
+            .unwrap_or_default();
+        let system_defaults = serde_json::to_value(system_defaults)?;
+
+        let to_pointer =
+            |key: String| -> String { key.split('.').map(|part| format!("/{part}")).collect() };
+        let mut value = serde_json::to_value(self)?;
+        let policy = system.read_only_keys().into_iter().map(to_pointer);
+        let defaults = system
+            .default_keys()
+            .into_iter()
+            .map(to_pointer)
+            .filter(|pointer| value.pointer(pointer) == system_defaults.pointer(pointer));
+        let pointers: Vec<String> = policy.chain(defaults).collect();
+        for pointer in pointers {
+            let (parent, key) = pointer.rsplit_once('/').unwrap_or_default();
+            if let Some(serde_json::Value::Object(map)) = value.pointer_mut(parent) {
+                map.remove(key);
+            }
+        }
+
+        let Ok(yaml) = serde_saphyr::to_string(&value) else {
+            bail!("failed to serialize configuration");
+        };
+        let file_contents = Self::CONFIG_HEADER.to_string() + &yaml;
+        Self::write_file(file_path, &file_contents).await
+    }
+
+    /// Write a file through an intermediate rename, retrying for up to 5 seconds.
+    async fn write_file(file_path: &Path, file_contents: &str) -> Result<()> {
         let time_start = tokio::time::Instant::now();
 
         let mut last_error: Option<Error> = None;
@@ -148,4 +290,41 @@ impl Config {
         let rt = tokio::runtime::Runtime::new()?;
         rt.block_on(async { default_config.save_to_file(cfg_path).await })
     }
+
+    /// Migrate a configuration file from a previous format, if needed.
+    ///
+    /// The file is kept alongside as `memospot.v{version}.yaml`, unless there's one already.
+    /// Returns its path if the file was migrated.
+    pub async fn migrate_file(cfg_path: &Path) -> Result<Option<PathBuf>> {
+        let Ok(yaml) = tokio::fs::read_to_string(cfg_path).await else {
+            return Ok(None);
+        };
+        let previous: Dict = Figment::from(Yaml::string(&yaml)).extract()?;
+        let mut migrated = previous.clone();
+        let Some(version) = Migrator::migrate(&mut migrated)? else {
+            return Ok(None);
+        };
+
+        let backup = cfg_path.with_extension(format!("v{version}.yaml"));
+        if !backup.exists() {
+            tokio::fs::copy(cfg_path, &backup).await?;
+        }
+
+        let previous = serde_json::to_value(&previous)?;
+        let current = serde_json::to_value(&migrated)?;
+        let updated = match document::update(&yaml, &previous, &current) {
+            Ok(updated) => updated,
+            Err(_) => Self::CONFIG_HEADER.to_string() + &serde_saphyr::to_string(&migrated)?,
+        };
+        Self::write_file(cfg_path, &updated).await?;
+        Ok(Some(backup))
+    }
+
+    /// Migrate a configuration file from a previous format, if needed.
+    ///
+    /// Blocking version of `migrate_file`.
+    pub fn migrate_file_blocking(cfg_path: &Path) -> Result<Option<PathBuf>> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(Self::migrate_file(cfg_path))
+    }
 }
@@ -0,0 +1,367 @@
+//! Comment-preserving updates of the configuration file.
+//!
+//! Only changed keys are rewritten. Comments, key order, formatting and unknown keys elsewhere
+//! in the document are left untouched. This is a line-based editor for block-style YAML, so
+//! callers must be ready to fall back to a full rewrite if [`update`] fails.
+
+use anyhow::{Result, bail};
+use serde_json::{Map, Value};
+
+/// Indentation of nested keys rendered by `serde_saphyr`, also used for documents without
+/// nested keys.
+const INDENT: usize = 2;
+
+/// A key in the document, along with the lines of its value.
+struct Entry {
+    /// Index of the key line.
+    line: usize,
+    indent: usize,
+    /// One past the last line of the value.
+    end: usize,
+    /// Whether the value is on the key line, like `key: value`.
+    inline: bool,
+}
+
+/// Apply the differences between `previous` and `current` to the YAML document `yaml`.
+///
+/// Fails if the updated document doesn't parse back to the original one with the changed
+/// values replaced.
+pub(crate) fn update(yaml: &str, previous: &Value, current: &Value) -> Result<String> {
+    let mut changes = Vec::new();
+    diff(&mut Vec::new(), previous, current, &mut changes);
+    if changes.is_empty() {
+        return Ok(yaml.to_string());
+    }
+
+    if changes.iter().any(Vec::is_empty) {
+        bail!("unable to replace the whole document");
+    }
+
+    let mut lines: Vec<String> = yaml.lines().map(String::from).collect();
+    for path in &changes {
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        set(&mut lines, &path, current)?;
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+
+    let mut expected: Value = serde_saphyr::from_str(yaml)?;
+    for path in &changes {
+        insert(&mut expected, path, value_at(current, path)?.clone());
+    }
+    let parsed: Value = serde_saphyr::from_str(&updated)?;
+    if parsed != expected {
+        bail!("failed to update the document");
+    }
+    Ok(updated)
+}
+
+/// Set the value at `path`, replacing anything but mappings along the way.
+fn insert(target: &mut Value, path: &[String], value: Value) {
+    match path.split_first() {
+        None => *target = value,
+        Some((key, rest)) => {
+            if !target.is_object() {
+                *target = Value::Object(Map::new());
+            }
+            if let Value::Object(map) = target {
+                insert(map.entry(key.clone()).or_insert(Value::Null), rest, value);
+            }
+        }
+    }
+}
+
+/// Collect the paths of changed values.
+///
+/// Mappings are compared key by key, unless keys were removed. Anything else is replaced
+/// as a whole.
+fn diff(
+    path: &mut Vec<String>,
+    previous: &Value,
+    current: &Value,
+    changes: &mut Vec<Vec<String>>,
+) {
+    match (previous, current) {
+        (Value::Object(previous), Value::Object(current))
+            if previous.keys().all(|key| current.contains_key(key)) =>
+        {
+            for (key, value) in current {
+                path.push(key.clone());
+                match previous.get(key) {
+                    Some(previous) => diff(path, previous, value, changes),
+                    None => changes.push(path.clone()),
+                }
+                path.pop();
+            }
+        }
+        _ if previous != current => changes.push(path.clone()),
+        _ => {}
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: &[impl AsRef<str>]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(value, |value, key| value.get(key.as_ref()))
+}
+
+fn value_at<'a>(value: &'a Value, path: &[impl AsRef<str>]) -> Result<&'a Value> {
+    match lookup(value, path) {
+        Some(value) => Ok(value),
+        None => {
+            let path: Vec<&str> = path.iter().map(AsRef::as_ref).collect();
+            bail!("missing value for `{}`", path.join("."))
+        }
+    }
+}
+
+/// Set the value at `path` to the one in `current`, adding missing parent keys.
+fn set(lines: &mut Vec<String>, path: &[&str], current: &Value) -> Result<()> {
+    // Deepest key of `path` present in the document.
+    let mut depth = path.len();
+    let mut found = None;
+    while depth > 0 {
+        if let Some(entry) = find(lines, &path[..depth]) {
+            found = Some(entry);
+            break;
+        }
+        depth -= 1;
+    }
+
+    match found {
+        Some(entry) if depth == path.len() || entry.inline => {
+            // Replace the value. Keys can't be added under an inline value, so it's
+            // replaced as a whole.
+            let key = path[depth - 1];
+            let value = value_at(current, &path[..depth])?;
+            let comment = value_of(&lines[entry.line])
+                .and_then(comment)
+                .map(String::from);
+            let mut rendered = render(key, value, entry.indent, step_of(lines))?;
+            if let Some(comment) = comment {
+                rendered[0].push_str(&comment);
+            }
+            lines.splice(entry.line..entry.end, rendered);
+        }
+        Some(entry) => {
+            let indent = child_indent(lines, &entry);
+            let step = indent - entry.indent;
+            let rendered =
+                render_nested(&path[depth..], current, &path[..depth], indent, step)?;
+            lines.splice(entry.end..entry.end, rendered);
+        }
+        None => {
+            let rendered = render_nested(path, current, &[], 0, step_of(lines))?;
+            lines.extend(rendered);
+        }
+    }
+    Ok(())
+}
+
+/// Render `keys`, nested into each other, with the value at `parent` + `keys`.
+fn render_nested(
+    keys: &[&str],
+    current: &Value,
+    parent: &[&str],
+    indent: usize,
+    step: usize,
+) -> Result<Vec<String>> {
+    let full_path: Vec<&str> = parent.iter().chain(keys).copied().collect();
+    let mut value = value_at(current, &full_path)?.clone();
+    for key in keys[1..].iter().rev() {
+        value = Value::Object(Map::from_iter([(key.to_string(), value)]));
+    }
+    render(keys[0], &value, indent, step)
+}
+
+/// Render `key: value` as block YAML lines, indented by `indent` spaces and nested by `step`.
+fn render(key: &str, value: &Value, indent: usize, step: usize) -> Result<Vec<String>> {
+    let mapping = Value::Object(Map::from_iter([(key.to_string(), value.clone())]));
+    let yaml = serde_saphyr::to_string(&mapping)?;
+    Ok(reindent(&yaml, indent, step))
+}
+
+/// Re-indent YAML rendered by `serde_saphyr` to start at `indent` and nest by `step`.
+///
+/// Lines after a sequence dash stay aligned with it, and block scalars keep the relative
+/// indentation of their lines.
+fn reindent(yaml: &str, indent: usize, step: usize) -> Vec<String> {
+    // Rendered and new indentation of the enclosing lines.
+    let mut levels = vec![(0, indent)];
+    // Rendered indentation of a block scalar header, and the rendered and new indentation
+    // of its first line.
+    let mut scalar: Option<(usize, Option<(usize, usize)>)> = None;
+    let mut lines = Vec::new();
+    for line in yaml.lines() {
+        let rendered = indent_of(line);
+        let content = line.trim_start();
+        if let Some((header, first)) = &mut scalar {
+            if content.is_empty() {
+                lines.push(String::new());
+                continue;
+            }
+            if rendered > *header {
+                let (from, to) =
+                    *first.get_or_insert_with(|| (rendered, nest(&mut levels, rendered, step)));
+                lines.push(format!("{}{content}", " ".repeat(to + rendered - from)));
+                continue;
+            }
+            scalar = None;
+        }
+
+        let new = nest(&mut levels, rendered, step);
+        let mut item = content;
+        let mut offset = 0;
+        while let Some(rest) = item.strip_prefix("- ") {
+            offset += 2;
+            levels.push((rendered + offset, new + offset));
+            item = rest;
+        }
+        if is_block_scalar(item) {
+            scalar = Some((rendered, None));
+        }
+        lines.push(format!("{}{content}", " ".repeat(new)));
+    }
+    lines
+}
+
+/// New indentation of a line rendered at `rendered`, nested under `levels`.
+fn nest(levels: &mut Vec<(usize, usize)>, rendered: usize, step: usize) -> usize {
+    while levels.last().is_some_and(|(level, _)| *level > rendered) {
+        levels.pop();
+    }
+    match levels.last().copied() {
+        Some((level, new)) if level == rendered => new,
+        parent => {
+            let new = parent.map_or(rendered, |(_, new)| new + step);
+            levels.push((rendered, new));
+            new
+        }
+    }
+}
+
+/// Whether a rendered `key: value` or sequence item starts a block scalar, like `key: |-`.
+fn is_block_scalar(item: &str) -> bool {
+    let value = item.rsplit_once(": ").map_or(item, |(_, value)| value);
+    value.starts_with(['|', '>'])
+        && value[1..]
+            .chars()
+            .all(|c| matches!(c, '-' | '+' | '0'..='9'))
+}
+
+/// Indentation of the children of `entry`, or the document's one if it has none.
+fn child_indent(lines: &[String], entry: &Entry) -> usize {
+    lines[entry.line + 1..entry.end]
+        .iter()
+        .find(|line| is_content(line))
+        .map(|line| indent_of(line))
+        .filter(|indent| *indent > entry.indent)
+        .unwrap_or(entry.indent + step_of(lines))
+}
+
+/// Indentation step of the document, from its least indented nested key.
+fn step_of(lines: &[String]) -> usize {
+    lines
+        .iter()
+        .filter(|line| is_content(line) && !line.trim_start().starts_with('-'))
+        .map(|line| indent_of(line))
+        .filter(|indent| *indent > 0)
+        .min()
+        .unwrap_or(INDENT)
+}
+
+/// Text after the key of a `key: value` line.
+fn value_of(line: &str) -> Option<&str> {
+    line.trim_start().split_once(':').map(|(_, value)| value)
+}
+
+/// Trailing comment of a value, with its leading whitespace.
+fn comment(value: &str) -> Option<&str> {
+    let body = value.trim_start();
+    // Skip a quoted string, which may contain `#`.
+    let skip = match body.chars().next() {
+        Some(quote @ ('"' | '\'')) => body[1..].find(quote).map_or(body.len(), |i| i + 2),
+        _ => 0,
+    };
+    let start = value.len() - body.len() + skip;
+    let (index, _) = value[start..].char_indices().find(|&(i, c)| {
+        c == '#' && (start + i == 0 || value[..start + i].ends_with(char::is_whitespace))
+    })?;
+    let end = value[..start + index].trim_end().len();
+    Some(&value[end..])
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Whether `line` has anything but whitespace and comments.
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+/// Find the key at `path` in a block-style YAML document.
+fn find(lines: &[String], path: &[&str]) -> Option<Entry> {
+    let line = find_key(lines.iter().map(String::as_str), path)?;
+    let indent = indent_of(&lines[line]);
+    let value = value_of(&lines[line])?;
+    let inline = comment(value).map_or(value, |c| &value[..value.len() - c.len()]);
+
+    // The value spans more indented lines, and sequence items at the same indentation.
+    let mut end = line + 1;
+    for (index, next) in lines.iter().enumerate().skip(line + 1) {
+        if !is_content(next) {
+            continue;
+        }
+        let next_indent = indent_of(next);
+        if next_indent > indent || (next_indent == indent && next.trim_start().starts_with('-'))
+        {
+            end = index + 1;
+        } else {
+            break;
+        }
+    }
+
+    Some(Entry {
+        line,
+        indent,
+        end,
+        inline: !inline.trim().is_empty(),
+    })
+}
+
+/// Find the 0-based line of the key at `path` in a block-style YAML document.
+fn find_key<'a>(lines: impl Iterator<Item = &'a str>, path: &[&str]) -> Option<usize> {
+    if path.is_empty() {
+        return None;
+    }
+    // Keys of the current line's ancestors, with their indentation.
+    let mut parents: Vec<(usize, &str)> = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let trimmed = line.trim_start();
+        if !is_content(line) || trimmed.starts_with('-') {
+            continue;
+        }
+        let Some((key, _)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().trim_matches(['"', '\'']);
+        let indent = indent_of(line);
+
+        while parents.last().is_some_and(|(i, _)| *i >= indent) {
+            parents.pop();
+        }
+        parents.push((indent, key));
+
+        if parents.len() == path.len() && parents.iter().map(|(_, k)| k).eq(path.iter()) {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// Find the 1-based line of the key at `path` in a block-style YAML document.
+pub(crate) fn find_line(yaml: &str, path: &[&str]) -> Option<usize> {
+    find_key(yaml.lines(), path).map(|index| index + 1)
+}
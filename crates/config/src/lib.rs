@@ -3,12 +3,27 @@
 mod tests;
 
 mod config;
+mod document;
 mod log;
 mod memos;
 mod memospot;
 mod migration;
+mod portable;
+mod provenance;
+mod restart;
+mod secrets;
+mod system;
+mod validate;
 
 pub use config::Config;
 pub use log::Log;
-pub use memos::Memos;
-pub use memospot::Memospot;
+pub use memos::{Memos, ResourceLimits};
+pub use memospot::{
+    Diagnostics, Memospot, Monitor, QuickCapture, is_secret_key, redact_secrets,
+};
+pub use migration::CONFIG_VERSION;
+pub use portable::{ExportFormat, ExportOptions};
+pub use provenance::{Origin, Provenance};
+pub use secrets::{SECRET_PREFIX, SecretVault, resolve_secrets};
+pub use system::SystemConfig;
+pub use validate::ValidationError;
@@ -1,10 +1,13 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 // https://github.com/estk/log4rs/blob/main/docs/Configuration.md
 
-#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct Log {
+    #[schemars(extend("x-restart" = true))]
     pub enabled: Option<bool>,
 }
 impl Default for Log {
@@ -1,12 +1,16 @@
 //! Memos configuration
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use ts_rs::TS;
 
-#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct EnvironmentVariables {
+    #[schemars(extend("x-restart" = true))]
     pub enabled: Option<bool>,
+    #[schemars(extend("x-restart" = true))]
     pub vars: Option<HashMap<String, String>>,
 }
 impl Default for EnvironmentVariables {
@@ -18,17 +22,61 @@ impl Default for EnvironmentVariables {
     }
 }
 
+/// Resource limits for the Memos server.
+///
+/// Linux only. Ignored on other platforms.
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
+pub struct ResourceLimits {
+    #[schemars(extend("x-restart" = true))]
+    pub enabled: Option<bool>,
+    /// Maximum virtual memory, in MiB.
+    #[schemars(extend("x-restart" = true))]
+    pub max_memory_mb: Option<u64>,
+    /// Maximum number of open files.
+    #[schemars(extend("x-restart" = true))]
+    pub max_open_files: Option<u64>,
+    /// Maximum CPU time, in seconds. The server is killed once exceeded.
+    #[schemars(extend("x-restart" = true))]
+    pub max_cpu_seconds: Option<u64>,
+    /// Scheduling priority, from -20 (highest) to 19 (lowest).
+    ///
+    /// Raising the priority requires privileges. Without them, it's left unchanged.
+    #[schemars(extend("x-restart" = true))]
+    pub nice: Option<i32>,
+    /// Prevent the server from gaining privileges through setuid binaries.
+    #[schemars(extend("x-restart" = true))]
+    pub no_new_privileges: Option<bool>,
+}
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            enabled: Some(false),
+            max_memory_mb: None,
+            max_open_files: None,
+            max_cpu_seconds: None,
+            nice: None,
+            no_new_privileges: None,
+        }
+    }
+}
+
 /// Memos configuration.
-#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct Memos {
     /// Memos binary path.
+    #[schemars(extend("x-restart" = true))]
     pub binary_path: Option<String>,
     /// Memos current working directory.
+    #[schemars(extend("x-restart" = true))]
     pub working_dir: Option<String>,
     /// Directory where Memos will store its database and assets.
+    #[schemars(extend("x-restart" = true))]
     pub data: Option<String>,
     /// Use demo mode with pre-seeded data. This is intended for development and testing purposes,
     /// and should not be used in production, as data is purged on each run.
+    #[schemars(extend("x-restart" = true))]
     pub demo: Option<bool>,
     /// DEPRECATED: `MEMOS_MODE` is now retired starting from v0.26.0.
     /// Database is always in `prod` mode unless `MEMOS_DEMO=true` is set.
@@ -39,21 +87,26 @@ pub struct Memos {
     /// - prod
     /// - dev
     /// - demo
+    #[schemars(extend("x-restart" = true))]
     pub mode: Option<String>,
     /// Server address.
     ///
     /// This should be "127.0.0.1" whenever running under Memospot.
     ///
     /// Binding to all addresses "0.0.0.0" will trigger a firewall warning on Windows.
+    #[schemars(extend("x-restart" = true))]
     pub addr: Option<String>,
     /// Last port used by Memos.
     ///
     /// Memospot will try to reuse this port on subsequent runs, and will find a new
     /// free port if the previous one is already in use or if this value is set to 0.
+    #[schemars(extend("x-restart" = true))]
     pub port: Option<u16>,
 
     /// Custom environment variables to pass to Memos.
     pub env: EnvironmentVariables,
+    /// Resource limits and sandboxing.
+    pub limits: ResourceLimits,
     // Memos server log settings.
     // pub log: Log,
 }
@@ -68,6 +121,7 @@ impl Default for Memos {
             addr: Some("127.0.0.1".to_string()),
             port: Some(5230),
             env: EnvironmentVariables::default(),
+            limits: ResourceLimits::default(),
         }
     }
 }
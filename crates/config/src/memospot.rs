@@ -1,14 +1,19 @@
 use std::collections::HashMap;
 
 use crate::log::Log;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use ts_rs::TS;
 
-#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct Backups {
     /// Enable backups.
+    #[schemars(extend("x-restart" = true))]
     pub enabled: Option<bool>,
     /// Directory where Memospot will store backups.
+    #[schemars(extend("x-restart" = true))]
     pub path: Option<String>,
 }
 impl Default for Backups {
@@ -19,10 +24,81 @@ impl Default for Backups {
         }
     }
 }
-#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
+pub struct Diagnostics {
+    /// Keys whose values are redacted from diagnostics bundles.
+    ///
+    /// Matches configuration keys and environment variable names containing any of these,
+    /// case-insensitively.
+    #[schemars(extend("x-restart" = false))]
+    pub redact_keys: Option<Vec<String>>,
+    /// Redact the values of all user-provided environment variables.
+    #[schemars(extend("x-restart" = false))]
+    pub redact_env_values: Option<bool>,
+}
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self {
+            redact_keys: Some(
+                ["token", "password", "secret", "key", "dsn", "credential"]
+                    .map(String::from)
+                    .to_vec(),
+            ),
+            redact_env_values: Some(true),
+        }
+    }
+}
+impl Diagnostics {
+    /// Lowercase `redact_keys`, for [`is_secret_key`] and [`redact_secrets`].
+    pub fn redact_patterns(&self) -> Vec<String> {
+        self.redact_keys
+            .iter()
+            .flatten()
+            .map(|key| key.to_lowercase())
+            .collect()
+    }
+}
+
+/// Whether `key` contains any of the lowercase `patterns`, case-insensitively.
+pub fn is_secret_key(key: &str, patterns: &[String]) -> bool {
+    let key = key.to_lowercase();
+    patterns
+        .iter()
+        .any(|pattern| key.contains(pattern.as_str()))
+}
+
+/// Redact the string values of keys matching the lowercase `patterns`, recursively.
+///
+/// Values are replaced with `replacement`, or removed if it's `None`.
+pub fn redact_secrets(value: &mut Value, patterns: &[String], replacement: Option<&str>) {
+    match value {
+        Value::Object(map) => {
+            if replacement.is_none() {
+                map.retain(|key, value| !(value.is_string() && is_secret_key(key, patterns)));
+            }
+            for (key, value) in map.iter_mut() {
+                match replacement {
+                    Some(replacement) if value.is_string() && is_secret_key(key, patterns) => {
+                        *value = Value::from(replacement);
+                    }
+                    _ => redact_secrets(value, patterns, replacement),
+                }
+            }
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| redact_secrets(value, patterns, replacement)),
+        _ => {}
+    }
+}
+
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct Migrations {
     /// Enable database migrations.
     /// Can be disabled to use Memospot with an uncertified Memos version.
+    #[schemars(extend("x-restart" = true))]
     pub enabled: Option<bool>,
 }
 impl Default for Migrations {
@@ -33,11 +109,15 @@ impl Default for Migrations {
     }
 }
 
-#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct RemoteServer {
     /// Enable remote server. This will disable spawning a local Memos server.
+    #[schemars(extend("x-restart" = true))]
     pub enabled: Option<bool>,
+    #[schemars(extend("x-restart" = true))]
     pub url: Option<String>,
+    #[schemars(extend("x-restart" = true))]
     pub user_agent: Option<String>,
 }
 impl Default for RemoteServer {
@@ -50,9 +130,12 @@ impl Default for RemoteServer {
     }
 }
 
-#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct EnvironmentVariables {
+    #[schemars(extend("x-restart" = true))]
     pub enabled: Option<bool>,
+    #[schemars(extend("x-restart" = true))]
     pub vars: Option<HashMap<String, String>>,
 }
 impl Default for EnvironmentVariables {
@@ -64,10 +147,14 @@ impl Default for EnvironmentVariables {
     }
 }
 
-#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct Updater {
+    #[schemars(extend("x-restart" = true))]
     pub enabled: Option<bool>,
+    #[schemars(extend("x-restart" = true))]
     pub check_interval: Option<String>,
+    #[schemars(extend("x-restart" = true))]
     pub last_check: Option<u64>,
 }
 impl Default for Updater {
@@ -80,32 +167,118 @@ impl Default for Updater {
     }
 }
 
-#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
+pub struct Monitor {
+    /// Sample the Memos server resource usage.
+    #[schemars(extend("x-restart" = false))]
+    pub enabled: Option<bool>,
+    /// Sampling interval, in seconds.
+    #[schemars(extend("x-restart" = false))]
+    pub interval: Option<u64>,
+    /// Log a warning when CPU usage exceeds this percentage. 100% is one full core.
+    #[schemars(extend("x-restart" = false))]
+    pub warn_cpu_percent: Option<f32>,
+    /// Log a warning when resident memory exceeds this size, in MiB.
+    #[schemars(extend("x-restart" = false))]
+    pub warn_memory_mb: Option<u64>,
+    /// Log a warning when the number of open files exceeds this.
+    #[schemars(extend("x-restart" = false))]
+    pub warn_open_files: Option<u64>,
+    /// Log a warning when the database WAL file exceeds this size, in MiB.
+    #[schemars(extend("x-restart" = false))]
+    pub warn_wal_mb: Option<u64>,
+}
+impl Default for Monitor {
+    fn default() -> Self {
+        Self {
+            enabled: Some(true),
+            interval: Some(10),
+            warn_cpu_percent: None,
+            warn_memory_mb: None,
+            warn_open_files: None,
+            warn_wal_mb: None,
+        }
+    }
+}
+
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
+pub struct QuickCapture {
+    /// Enable the global quick-capture hotkey.
+    #[schemars(extend("x-restart" = true))]
+    pub enabled: Option<bool>,
+    /// OS-global hotkey that opens the quick-capture window, like `CmdOrCtrl+Shift+M`.
+    #[schemars(extend("x-restart" = true))]
+    pub shortcut: Option<String>,
+    /// Memos access token used to create memos.
+    ///
+    /// Store it in the secrets vault and reference it as `secret:<name>` to keep it out of
+    /// this file.
+    #[schemars(extend("x-restart" = false))]
+    pub access_token: Option<String>,
+    /// Visibility of captured memos: `PRIVATE`, `PROTECTED` or `PUBLIC`.
+    #[schemars(extend("x-restart" = false))]
+    pub visibility: Option<String>,
+}
+impl Default for QuickCapture {
+    fn default() -> Self {
+        Self {
+            enabled: Some(false),
+            shortcut: Some("CmdOrCtrl+Shift+M".to_string()),
+            access_token: None,
+            visibility: Some("PRIVATE".to_string()),
+        }
+    }
+}
+
+#[derive(TS, JsonSchema, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct Window {
     /// Whether the window should be centered upon creation.
+    #[schemars(extend("x-restart" = true))]
     pub center: Option<bool>,
     /// Whether the window should be fullscreen upon creation.
+    #[schemars(extend("x-restart" = true))]
     pub fullscreen: Option<bool>,
     /// Whether the window should be resizable.
+    #[schemars(extend("x-restart" = true))]
     pub resizable: Option<bool>,
     /// (Managed) Whether the window should be maximized upon creation.
+    #[schemars(extend("x-restart" = true))]
     pub maximized: Option<bool>,
     /// (Managed) The window's initial width.
+    #[schemars(extend("x-restart" = true))]
     pub width: Option<u32>,
     /// (Managed) The window's initial height.
+    #[schemars(extend("x-restart" = true))]
     pub height: Option<u32>,
     /// (Managed) The window's initial x position.
+    #[schemars(extend("x-restart" = true))]
     pub x: Option<i32>,
     /// (Managed) The window's initial y position.
+    #[schemars(extend("x-restart" = true))]
     pub y: Option<i32>,
     /// Hide main menu bar.
+    #[schemars(extend("x-restart" = true))]
     pub hide_menu_bar: Option<bool>,
     /// Theme.
+    #[schemars(extend("x-restart" = false))]
     pub theme: Option<String>,
     /// Reduce animations in the interface.
+    #[schemars(extend("x-restart" = false))]
     pub reduce_animation: Option<bool>,
     /// Locale.
+    #[schemars(extend("x-restart" = false))]
     pub locale: Option<String>,
+    /// Show an icon in the system tray.
+    #[schemars(extend("x-restart" = true))]
+    pub tray_icon: Option<bool>,
+    /// Keep running in the system tray when the main window is closed.
+    ///
+    /// Shows the tray icon regardless of `tray_icon`.
+    #[schemars(extend("x-restart" = true))]
+    pub close_to_tray: Option<bool>,
 }
 impl Default for Window {
     fn default() -> Self {
@@ -122,20 +295,29 @@ impl Default for Window {
             theme: None,
             reduce_animation: Some(false),
             locale: None,
+            tray_icon: Some(false),
+            close_to_tray: Some(false),
         }
     }
 }
 
-#[derive(TS, Default, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(TS, JsonSchema, Default, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[schemars(default)]
 pub struct Memospot {
     /// Backups settings.
     pub backups: Backups,
+    /// Diagnostics bundle settings.
+    pub diagnostics: Diagnostics,
     /// Custom system environment variables to pass to Memospot.
     pub env: EnvironmentVariables,
     /// Database migrations settings.
     pub migrations: Migrations,
     // Log settings.
     pub log: Log,
+    /// Memos server resource monitoring settings.
+    pub monitor: Monitor,
+    /// Quick-capture settings.
+    pub quick_capture: QuickCapture,
     /// Remote server settings.
     pub remote: RemoteServer,
     /// Updater settings.
@@ -1,34 +1,133 @@
-use std::collections::HashMap;
+//! Configuration format migrations.
+//!
+//! Each migration upgrades the configuration, as read from the file, by one version.
+//! Files without a `config_version` predate versioning and are at version 0.
 
-use figment::{providers::Serialized, util::map};
+use anyhow::{Result, bail};
+use figment::value::{Dict, Value};
+use figment::{Figment, providers::Serialized};
 
-use crate::config::CONFIG_PROFILE;
+/// Current configuration format version.
+pub const CONFIG_VERSION: u32 = 1;
 
-pub trait MigrationExt {
-    fn migrate(self) -> figment::Figment;
-    fn v_0_1_7_to_v1_0_0(self) -> figment::Figment;
+/// A configuration format migration.
+pub trait ConfigMigration {
+    /// Version upgraded from.
+    fn source_version(&self) -> u32;
+    /// Version upgraded to.
+    fn target_version(&self) -> u32;
+    /// Upgrade the configuration.
+    fn up(&self, config: &mut Dict) -> Result<()>;
 }
 
-impl MigrationExt for figment::Figment {
-    /// Migrate previous configuration format to the current one.
-    fn migrate(self) -> figment::Figment {
-        self.v_0_1_7_to_v1_0_0()
+pub struct Migrator;
+
+impl Migrator {
+    /// Migration list, in order.
+    pub fn migrations() -> Vec<Box<dyn ConfigMigration>> {
+        vec![Box::new(V0ToV1)]
     }
 
-    /// Upgrade from Memospot v0.1.7.
+    /// Version of `config`.
+    pub fn version(config: &Dict) -> Result<u32> {
+        match config.get("config_version") {
+            None | Some(Value::Empty(..)) => Ok(0),
+            Some(value) => match value.to_u128().map(u32::try_from) {
+                Some(Ok(version)) => Ok(version),
+                _ => bail!("invalid config_version: expected a version number"),
+            },
+        }
+    }
+
+    /// Apply pending migrations to `config`.
+    ///
+    /// Returns the version migrated from, or `None` if it was up to date.
+    /// Configurations from newer versions are left untouched.
+    pub fn migrate(config: &mut Dict) -> Result<Option<u32>> {
+        let from = Self::version(config)?;
+        if from >= CONFIG_VERSION {
+            return Ok(None);
+        }
+        let mut version = from;
+        for migration in Self::migrations() {
+            if migration.source_version() == version {
+                migration.up(config)?;
+                version = migration.target_version();
+            }
+        }
+        if version != CONFIG_VERSION {
+            bail!("no configuration migration from version {version}");
+        }
+        config.insert("config_version".into(), version.into());
+        Ok(Some(from))
+    }
+}
+
+pub trait MigrationExt {
+    fn migrate(self) -> Figment;
+}
+
+impl MigrationExt for Figment {
+    /// Migrate previous configuration formats to the current one.
     ///
-    /// Overrides `memos.env` with the new structure format, preventing errors if `memos.env=null`.
-    fn v_0_1_7_to_v1_0_0(self) -> figment::Figment {
-        if let Ok(previous_vars) = self.extract_inner::<HashMap<String, String>>("memos.env") {
-            let memos_env_enabled = map!["memos"  => map!["env" => map!["enabled" => true]]];
-            let memos_env_vars = map!["memos"  => map!["env" => map!["vars" => previous_vars]]];
-            if !memos_env_vars.is_empty() {
-                return self
-                    .merge(Serialized::from(memos_env_enabled, CONFIG_PROFILE))
-                    .merge(Serialized::from(memos_env_vars, CONFIG_PROFILE));
+    /// The figment is left untouched if it's up to date, only lacks its version, or can't be
+    /// extracted, so that value metadata is kept. Extraction errors are then reported by
+    /// the caller.
+    fn migrate(self) -> Figment {
+        let Ok(mut original) = self.extract::<Dict>() else {
+            return self;
+        };
+        let mut config = original.clone();
+        if !matches!(Migrator::migrate(&mut config), Ok(Some(_))) {
+            return self;
+        }
+        original.remove("config_version");
+        config.remove("config_version");
+        match config == original {
+            true => self,
+            false => Figment::from(Serialized::defaults(config)),
+        }
+    }
+}
+
+/// Upgrade from Memospot v0.1.7.
+///
+/// `memos.env` used to be a plain map of environment variables. They're now under
+/// `memos.env.vars`, and enabled by `memos.env.enabled`. An empty `memos.env` is removed,
+/// so that it's reset to its default.
+struct V0ToV1;
+
+impl ConfigMigration for V0ToV1 {
+    fn source_version(&self) -> u32 {
+        0
+    }
+
+    fn target_version(&self) -> u32 {
+        1
+    }
+
+    fn up(&self, config: &mut Dict) -> Result<()> {
+        let Some(Value::Dict(_, memos)) = config.get_mut("memos") else {
+            return Ok(());
+        };
+        let env = match memos.get("env") {
+            Some(Value::Dict(_, env)) => env,
+            Some(Value::Empty(..)) => {
+                memos.remove("env");
+                return Ok(());
             }
+            _ => return Ok(()),
+        };
+        let is_previous_format = !env.is_empty()
+            && env.values().all(|value| matches!(value, Value::String(..)))
+            && !env.contains_key("vars");
+        if is_previous_format {
+            let vars = Value::from(env.clone());
+            let mut env = Dict::new();
+            env.insert("enabled".into(), true.into());
+            env.insert("vars".into(), vars);
+            memos.insert("env".into(), env.into());
         }
-        let memos_env_enabled = map!["memos"  => map!["env" => map!["enabled" => false]]];
-        self.merge(Serialized::from(memos_env_enabled, CONFIG_PROFILE))
+        Ok(())
     }
 }
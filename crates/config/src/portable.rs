@@ -0,0 +1,153 @@
+//! Portable configuration documents, to share a configuration between machines.
+//!
+//! Exports can leave out values only meaningful on the machine they were saved on, and
+//! secrets. Imports are merged into the current configuration, so values left out of the
+//! document are kept.
+
+use crate::config::Config;
+use crate::memospot::redact_secrets;
+use crate::migration::MigrationExt;
+
+use anyhow::{Error, Result, bail};
+use figment::Figment;
+use figment::providers::{Format, Serialized};
+use figment::value::Dict;
+use serde_json::{Map, Value};
+use serde_saphyr::figment::Yaml;
+use std::path::Path;
+
+/// Keys only meaningful on the machine they were saved on.
+const MACHINE_KEYS: [&str; 6] = [
+    "memospot.updater.last_check",
+    "memospot.window.maximized",
+    "memospot.window.width",
+    "memospot.window.height",
+    "memospot.window.x",
+    "memospot.window.y",
+];
+
+/// Path keys, left out when absolute. Paths relative to the home directory are portable.
+const PATH_KEYS: [&str; 4] = [
+    "memos.binary_path",
+    "memos.working_dir",
+    "memos.data",
+    "memospot.backups.path",
+];
+
+/// Environment variable maps, whose values may be secrets.
+const ENV_VARS_KEYS: [&str; 2] = ["memos.env.vars", "memospot.env.vars"];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ExportFormat {
+    Yaml,
+    Json,
+}
+
+/// What to leave out of an exported configuration.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ExportOptions {
+    /// Leave out window geometry, the last update check and absolute paths.
+    pub strip_machine: bool,
+    /// Leave out values of keys matching `memospot.diagnostics.redact_keys`, and the
+    /// environment variables, unless `memospot.diagnostics.redact_env_values` is off.
+    pub strip_secrets: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            strip_machine: true,
+            strip_secrets: true,
+        }
+    }
+}
+
+impl Config {
+    /// Export a portable configuration document.
+    pub fn export(
+        &self,
+        format: ExportFormat,
+        options: ExportOptions,
+    ) -> Result<String, Error> {
+        let settings = &self.memospot.diagnostics;
+        let mut value = serde_json::to_value(self)?;
+        if options.strip_secrets {
+            redact_secrets(&mut value, &settings.redact_patterns(), None);
+        }
+        let Value::Object(root) = &mut value else {
+            bail!("configuration is not a mapping");
+        };
+
+        if options.strip_machine {
+            for key in MACHINE_KEYS {
+                remove(root, key);
+            }
+            for key in PATH_KEYS {
+                let is_absolute = lookup(root, key)
+                    .and_then(Value::as_str)
+                    .is_some_and(|path| Path::new(path).is_absolute());
+                if is_absolute {
+                    remove(root, key);
+                }
+            }
+        }
+
+        if options.strip_secrets && settings.redact_env_values != Some(false) {
+            for key in ENV_VARS_KEYS {
+                remove(root, key);
+            }
+        }
+
+        Ok(match format {
+            ExportFormat::Yaml => serde_saphyr::to_string(&value)?,
+            ExportFormat::Json => serde_json::to_string_pretty(&value)? + "\n",
+        })
+    }
+
+    /// Configuration with the values of an exported YAML or JSON `document` merged in.
+    ///
+    /// Documents from previous versions are migrated. Fails if the result has invalid values.
+    pub fn import(&self, document: &str) -> Result<Config, Error> {
+        // YAML is a superset of JSON.
+        let imported: Dict = Figment::from(Yaml::string(document)).migrate().extract()?;
+
+        let config: Config = Figment::from(Serialized::defaults(self))
+            .merge(Serialized::defaults(imported))
+            .extract()?;
+        let errors = config.validate();
+        if !errors.is_empty() {
+            let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            bail!("invalid configuration: {}", errors.join("; "));
+        }
+        Ok(config)
+    }
+}
+
+fn lookup<'a>(root: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    let (parents, last) = key.rsplit_once('.').unwrap_or(("", key));
+    parents
+        .split('.')
+        .filter(|k| !k.is_empty())
+        .try_fold(root, |map, k| map.get(k)?.as_object())?
+        .get(last)
+}
+
+fn lookup_mut<'a>(root: &'a mut Map<String, Value>, key: &str) -> Option<&'a mut Value> {
+    let (parents, last) = key.rsplit_once('.').unwrap_or(("", key));
+    parents
+        .split('.')
+        .filter(|k| !k.is_empty())
+        .try_fold(root, |map, k| map.get_mut(k)?.as_object_mut())?
+        .get_mut(last)
+}
+
+fn remove(root: &mut Map<String, Value>, key: &str) {
+    let (parents, last) = key.rsplit_once('.').unwrap_or(("", key));
+    let parent = match parents {
+        "" => Some(root),
+        parents => lookup_mut(root, parents).and_then(Value::as_object_mut),
+    };
+    if let Some(parent) = parent {
+        parent.remove(last);
+    }
+}
@@ -0,0 +1,131 @@
+//! Configuration provenance: which layer set each value.
+
+use crate::config::{Config, ENV_PREFIX, env_provider};
+use crate::system::SystemConfig;
+
+use figment::Provider;
+use figment::Source;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::env;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// Configuration layer a value comes from.
+#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Origin {
+    /// Built-in default.
+    Default,
+    /// System-wide defaults file.
+    SystemDefaults { path: PathBuf },
+    /// User configuration file.
+    File { path: PathBuf },
+    /// Environment variable, like `MEMOSPOT_MEMOS__PORT`.
+    Env { var: String },
+    /// System-wide policy file. The value can't be changed.
+    Policy { path: PathBuf },
+}
+
+/// Effective value of a configuration key, and where it comes from.
+#[derive(TS, Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct Provenance {
+    /// Dotted key, like `memos.port`.
+    pub key: String,
+    #[ts(type = "unknown")]
+    pub value: Value,
+    pub origin: Origin,
+    /// Profile the value was selected from.
+    pub profile: String,
+}
+
+impl Config {
+    /// Effective value and origin of each configuration key, in key order.
+    ///
+    /// Values are read from `self`, and origins from the configuration layers of `cfg_path`
+    /// and `system`. Values of a file pending migration are attributed to the file.
+    pub fn provenance(&self, cfg_path: &Path, system: &SystemConfig) -> Vec<Provenance> {
+        let layers = Config::layers(cfg_path, system);
+        let env_name = env_provider().metadata().name;
+
+        let mut leaves = Vec::new();
+        let value = serde_json::to_value(self).unwrap_or_default();
+        collect_leaves(&mut Vec::new(), &value, &mut leaves);
+
+        leaves
+            .into_iter()
+            .map(|(path, value)| {
+                let key = path.join(".");
+                let found = layers.find_value(&key).ok();
+                let metadata = found.as_ref().and_then(|v| layers.get_metadata(v.tag()));
+                let origin = match metadata {
+                    None => Origin::Default,
+                    Some(metadata) if metadata.name == env_name => Origin::Env {
+                        var: env_var(&path).unwrap_or_else(|| {
+                            ENV_PREFIX.to_string() + &path.join("__").to_ascii_uppercase()
+                        }),
+                    },
+                    Some(metadata) => match &metadata.source {
+                        Some(Source::File(file)) if *file == system.policy_file() => {
+                            Origin::Policy { path: file.clone() }
+                        }
+                        Some(Source::File(file)) if *file == system.defaults_file() => {
+                            Origin::SystemDefaults { path: file.clone() }
+                        }
+                        Some(Source::File(file)) => Origin::File { path: file.clone() },
+                        _ => Origin::File {
+                            path: cfg_path.to_path_buf(),
+                        },
+                    },
+                };
+                let profile = match (&origin, found.and_then(|v| v.tag().profile())) {
+                    (Origin::Default, _) | (_, None) => figment::Profile::Default,
+                    (_, Some(profile)) => profile,
+                };
+                Provenance {
+                    key,
+                    value,
+                    origin,
+                    profile: profile.to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Collect the leaf values of `value`, with their paths.
+fn collect_leaves(
+    path: &mut Vec<String>,
+    value: &Value,
+    leaves: &mut Vec<(Vec<String>, Value)>,
+) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                path.push(key.clone());
+                collect_leaves(path, value, leaves);
+                path.pop();
+            }
+        }
+        _ => leaves.push((path.clone(), value.clone())),
+    }
+}
+
+/// Name of the environment variable setting the key at `path`, or one of its parents.
+fn env_var(path: &[String]) -> Option<String> {
+    let key = path.join(".");
+    env::vars_os()
+        .filter_map(|(name, _)| name.into_string().ok())
+        .filter(|name| {
+            let Some(rest) = name
+                .get(..ENV_PREFIX.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(ENV_PREFIX))
+                .map(|_| &name[ENV_PREFIX.len()..])
+            else {
+                return false;
+            };
+            let rest = rest.to_ascii_lowercase().replace("__", ".");
+            key == rest || key.starts_with(&format!("{rest}."))
+        })
+        .max_by_key(String::len)
+}
@@ -0,0 +1,77 @@
+//! Restart classification of configuration keys.
+//!
+//! Each field is classified once, in the JSON Schema, with `x-restart`: `true` if changes
+//! only take effect after restarting Memospot, `false` if they apply live.
+
+use crate::config::Config;
+
+use serde_json::{Map, Value};
+use std::sync::LazyLock;
+
+/// JSON Schema keyword classifying a field.
+const RESTART_KEYWORD: &str = "x-restart";
+
+/// JSON Pointers to the settings that only take effect after restarting Memospot.
+static RESTART_POINTERS: LazyLock<Vec<String>> = LazyLock::new(|| {
+    Config::restart_classification()
+        .into_iter()
+        .filter(|(_, restart)| *restart == Some(true))
+        .map(|(key, _)| key.split('.').map(|part| format!("/{part}")).collect())
+        .collect()
+});
+
+impl Config {
+    /// Dotted keys of the configuration leaves, and whether changing them requires a
+    /// restart. `None` if the field is unclassified.
+    pub(crate) fn restart_classification() -> Vec<(String, Option<bool>)> {
+        let schema = schemars::schema_for!(Config).to_value();
+        let empty = Map::new();
+        let defs = schema
+            .get("$defs")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let mut leaves = Vec::new();
+        classify(&schema, defs, &mut Vec::new(), &mut leaves);
+        leaves
+    }
+
+    /// Whether `other` differs from `self` in settings that only take effect after
+    /// restarting Memospot.
+    pub fn restart_required(&self, other: &Config) -> bool {
+        let (Ok(this), Ok(other)) = (serde_json::to_value(self), serde_json::to_value(other))
+        else {
+            return self != other;
+        };
+        RESTART_POINTERS
+            .iter()
+            .any(|pointer| this.pointer(pointer) != other.pointer(pointer))
+    }
+}
+
+/// Collect the leaves of `schema`, with their classification.
+fn classify(
+    schema: &Value,
+    defs: &Map<String, Value>,
+    path: &mut Vec<String>,
+    leaves: &mut Vec<(String, Option<bool>)>,
+) {
+    let resolved = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix("#/$defs/"))
+        .and_then(|name| defs.get(name))
+        .unwrap_or(schema);
+    match resolved.get("properties").and_then(Value::as_object) {
+        Some(properties) => {
+            for (key, property) in properties {
+                path.push(key.clone());
+                classify(property, defs, path, leaves);
+                path.pop();
+            }
+        }
+        None => leaves.push((
+            path.join("."),
+            schema.get(RESTART_KEYWORD).and_then(Value::as_bool),
+        )),
+    }
+}
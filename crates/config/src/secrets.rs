@@ -0,0 +1,318 @@
+//! Secrets vault, to keep credentials out of `memospot.yaml`.
+//!
+//! Environment variable values can reference a secret as `secret:<name>`, resolved when the
+//! variables are set, and so can the quick-capture access token. Secrets are stored next to the configuration file in
+//! `memospot.secrets`, encrypted with ChaCha20-Poly1305 and a random key.
+//!
+//! The key, `memospot.secrets.key`, is kept outside the data directory, in a per-machine
+//! directory (see [`SecretVault::key_dir`]), so syncing or copying the data directory
+//! doesn't carry it along. Both files are only readable by the user.
+//!
+//! This keeps secrets out of `memospot.yaml`, exports and diagnostics bundles, and makes a
+//! copied data directory useless without the key. It doesn't protect them from programs run
+//! by the user, or from backups of the whole home directory.
+
+use anyhow::{Context, Error, Result, anyhow, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use homedir::HomeDirExt;
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Prefix of values referencing a secret, like `secret:s3_key`.
+pub const SECRET_PREFIX: &str = "secret:";
+
+const VAULT_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+
+/// Encrypted vault file.
+#[derive(Debug, Deserialize, Serialize)]
+struct VaultFile {
+    version: u32,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Secrets stored in an encrypted file.
+#[derive(Debug)]
+pub struct SecretVault {
+    file: PathBuf,
+    key_file: PathBuf,
+    secrets: BTreeMap<String, String>,
+}
+
+impl SecretVault {
+    /// File name of the vault, next to the configuration file.
+    pub const FILE_NAME: &str = "memospot.secrets";
+    /// File name of the vault key, in [`key_dir`](Self::key_dir).
+    pub const KEY_FILE_NAME: &str = "memospot.secrets.key";
+
+    /// Open the vault of the configuration file `config_file`.
+    ///
+    /// Starts empty if there's no vault yet. Fails if it can't be decrypted.
+    /// A key left next to the configuration file by a previous version is moved to
+    /// [`key_dir`](Self::key_dir).
+    pub fn open(config_file: &Path) -> Result<Self> {
+        let mut vault = Self {
+            file: config_file.with_file_name(Self::FILE_NAME),
+            key_file: Self::key_dir().join(Self::KEY_FILE_NAME),
+            secrets: BTreeMap::new(),
+        };
+        vault.move_legacy_key(&config_file.with_file_name(Self::KEY_FILE_NAME))?;
+        if vault.file.is_file() {
+            vault.secrets = vault.read()?;
+        }
+        Ok(vault)
+    }
+
+    /// Per-machine directory of the vault key, outside the data directory.
+    ///
+    /// `$XDG_STATE_HOME/memospot` (`~/.local/state/memospot`) on Linux,
+    /// `~/Library/Application Support/memospot` on macOS and `%LOCALAPPDATA%\memospot-keys`
+    /// on Windows, or `MEMOSPOT_SECRETS_KEY_DIR`, if set.
+    pub fn key_dir() -> PathBuf {
+        if let Some(dir) = env::var_os("MEMOSPOT_SECRETS_KEY_DIR") {
+            return PathBuf::from(dir);
+        }
+        #[cfg(target_os = "windows")]
+        let dir = env::var_os("LOCALAPPDATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| "~/AppData/Local".expand_home().unwrap_or_default())
+            .join("memospot-keys");
+        #[cfg(target_os = "macos")]
+        let dir = "~/Library/Application Support/memospot"
+            .expand_home()
+            .unwrap_or_default();
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let dir = env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| "~/.local/state".expand_home().unwrap_or_default())
+            .join("memospot");
+        dir
+    }
+
+    /// Names of the stored secrets, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.secrets.keys().map(String::as_str)
+    }
+
+    /// Value of secret `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.secrets.get(name).map(String::as_str)
+    }
+
+    /// Store secret `name`, and save the vault.
+    ///
+    /// Names may contain ASCII letters, digits, `_`, `-` and `.`.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        let is_valid = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.');
+        if name.is_empty() || !name.chars().all(is_valid) {
+            bail!("invalid secret name `{name}`. Use letters, digits, `_`, `-` and `.`");
+        }
+        self.secrets.insert(name.to_string(), value.to_string());
+        self.save()
+    }
+
+    /// Delete secret `name`, and save the vault.
+    ///
+    /// Returns whether the secret existed.
+    pub fn remove(&mut self, name: &str) -> Result<bool> {
+        if self.secrets.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Value referenced by `value`, if it's a secret reference, or `value` itself.
+    pub fn resolve<'a>(&'a self, value: &'a str) -> Result<&'a str> {
+        match secret_name(value) {
+            Some(name) => self
+                .get(name)
+                .ok_or_else(|| anyhow!("secret `{name}` not found")),
+            None => Ok(value),
+        }
+    }
+
+    /// Move `legacy_key` to the key directory, unless there's a key there already.
+    fn move_legacy_key(&self, legacy_key: &Path) -> Result<()> {
+        if !legacy_key.is_file() || self.key_file.exists() {
+            return Ok(());
+        }
+        let key = fs::read(legacy_key)
+            .with_context(|| format!("failed to read {}", legacy_key.display()))?;
+        create_key_dir(&self.key_file)?;
+        write_private(&self.key_file, &key)?;
+        fs::remove_file(legacy_key)
+            .with_context(|| format!("failed to remove {}", legacy_key.display()))
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>> {
+        let json = fs::read_to_string(&self.file)
+            .with_context(|| format!("failed to read {}", self.file.display()))?;
+        let vault: VaultFile = serde_json::from_str(&json)
+            .with_context(|| format!("invalid secrets vault {}", self.file.display()))?;
+        if vault.version != VAULT_VERSION {
+            bail!("unsupported secrets vault version {}", vault.version);
+        }
+
+        let nonce: [u8; NONCE_LEN] = BASE64
+            .decode(&vault.nonce)?
+            .try_into()
+            .map_err(|_| anyhow!("invalid secrets vault nonce"))?;
+        let mut data = BASE64.decode(&vault.ciphertext)?;
+        let plaintext = self
+            .key(false)?
+            .open_in_place(Nonce::assume_unique_for_key(nonce), aad(), &mut data)
+            .map_err(|_| {
+                anyhow!(
+                    "unable to decrypt {}. Was {} replaced?",
+                    self.file.display(),
+                    self.key_file.display()
+                )
+            })?;
+        Ok(serde_json::from_slice(plaintext)?)
+    }
+
+    fn save(&self) -> Result<()> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| anyhow!("failed to generate a nonce"))?;
+        let mut data = serde_json::to_vec(&self.secrets)?;
+        self.key(true)?
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), aad(), &mut data)
+            .map_err(|_| anyhow!("failed to encrypt the secrets vault"))?;
+
+        let vault = VaultFile {
+            version: VAULT_VERSION,
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(data),
+        };
+        write_private(&self.file, &serde_json::to_vec_pretty(&vault)?)?;
+        // The key file may predate the permission checks, or have been copied around.
+        set_private(&self.key_file)
+            .with_context(|| format!("failed to restrict {}", self.key_file.display()))
+    }
+
+    /// Vault key. A new one is created if `create` is set and there's none yet.
+    fn key(&self, create: bool) -> Result<LessSafeKey> {
+        let key = match fs::read(&self.key_file) {
+            Ok(key) => key,
+            Err(e) if create && e.kind() == std::io::ErrorKind::NotFound => {
+                let mut key = vec![0; KEY_LEN];
+                SystemRandom::new()
+                    .fill(&mut key)
+                    .map_err(|_| anyhow!("failed to generate a key"))?;
+                create_key_dir(&self.key_file)?;
+                write_private(&self.key_file, &key)?;
+                key
+            }
+            Err(e) => {
+                return Err(Error::new(e))
+                    .with_context(|| format!("failed to read {}", self.key_file.display()));
+            }
+        };
+        let key = UnboundKey::new(&CHACHA20_POLY1305, &key)
+            .map_err(|_| anyhow!("invalid secrets vault key {}", self.key_file.display()))?;
+        Ok(LessSafeKey::new(key))
+    }
+}
+
+/// Name of the secret referenced by `value`, like `s3_key` for `secret:s3_key`.
+pub fn secret_name(value: &str) -> Option<&str> {
+    value.strip_prefix(SECRET_PREFIX)
+}
+
+/// Environment variables `vars`, with secret references resolved from the vault of the
+/// configuration file `config_file`.
+///
+/// The vault is only opened if there are references. Variables whose secret can't be
+/// resolved are left out, and the errors returned along with the other variables.
+pub fn resolve_secrets(
+    vars: &HashMap<String, String>,
+    config_file: &Path,
+) -> (HashMap<String, String>, Vec<Error>) {
+    if !vars.values().any(|value| secret_name(value).is_some()) {
+        return (vars.clone(), Vec::new());
+    }
+
+    let vault = SecretVault::open(config_file);
+    let mut resolved = HashMap::new();
+    let mut errors = Vec::new();
+    for (key, value) in vars {
+        let value = match &vault {
+            Ok(vault) => vault.resolve(value),
+            Err(e) if secret_name(value).is_some() => Err(anyhow!("{e:#}")),
+            Err(_) => Ok(value.as_str()),
+        };
+        match value {
+            Ok(value) => {
+                resolved.insert(key.clone(), value.to_string());
+            }
+            Err(e) => errors.push(e.context(format!("environment variable `{key}`"))),
+        }
+    }
+    (resolved, errors)
+}
+
+fn aad() -> Aad<&'static [u8]> {
+    Aad::from(b"memospot.secrets")
+}
+
+/// Create the directory of `key_file`, only accessible by the user.
+fn create_key_dir(key_file: &Path) -> Result<()> {
+    let Some(dir) = key_file.parent() else {
+        return Ok(());
+    };
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder
+        .create(dir)
+        .with_context(|| format!("failed to create {}", dir.display()))
+}
+
+/// Write a file only readable by the user.
+///
+/// Uses an intermediate rename, so an interrupted write keeps the previous contents.
+fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp_file = path.with_file_name(format!("{}.tmp", Uuid::new_v4()));
+    let write = || -> io::Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options.open(&tmp_file)?;
+        set_private(&tmp_file)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp_file, path)
+    };
+    write()
+        .inspect_err(|_| {
+            fs::remove_file(&tmp_file).ok();
+        })
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Make a file only readable by the user, whatever its current permissions.
+fn set_private(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! System-wide configuration, managed by administrators.
+//!
+//! Configuration layers, from lowest to highest priority:
+//! 1. Built-in defaults.
+//! 2. System-wide defaults, `defaults.yaml`.
+//! 3. User configuration, `memospot.yaml`.
+//! 4. `MEMOSPOT_` environment variables.
+//! 5. System-wide policy, `policy.yaml`. Users can't change the keys it sets.
+//!
+//! The system files are looked up in `/etc/memospot` on Linux,
+//! `/Library/Application Support/memospot` on macOS and `%ProgramData%\memospot` on Windows,
+//! or in `MEMOSPOT_SYSTEM_CONFIG_DIR`, if set.
+
+use figment::Figment;
+use figment::providers::Format;
+use figment::value::{Dict, Value};
+use serde_saphyr::figment::Yaml;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Location of the system-wide configuration files.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SystemConfig {
+    dir: PathBuf,
+}
+
+impl SystemConfig {
+    pub const DEFAULTS_FILE: &'static str = "defaults.yaml";
+    pub const POLICY_FILE: &'static str = "policy.yaml";
+
+    /// System-wide configuration files in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// System-wide configuration files in the platform location.
+    pub fn locate() -> Self {
+        if let Some(dir) = env::var_os("MEMOSPOT_SYSTEM_CONFIG_DIR") {
+            return Self::new(dir);
+        }
+        #[cfg(target_os = "windows")]
+        let dir =
+            Path::new(&env::var_os("ProgramData").unwrap_or_else(|| "C:\\ProgramData".into()))
+                .join("memospot");
+        #[cfg(target_os = "macos")]
+        let dir = Path::new("/Library/Application Support/memospot").to_path_buf();
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let dir = Path::new("/etc/memospot").to_path_buf();
+        Self::new(dir)
+    }
+
+    pub fn defaults_file(&self) -> PathBuf {
+        self.dir.join(Self::DEFAULTS_FILE)
+    }
+
+    pub fn policy_file(&self) -> PathBuf {
+        self.dir.join(Self::POLICY_FILE)
+    }
+
+    /// Dotted keys set by the policy, like `memospot.remote.url`.
+    ///
+    /// Empty if there's no policy, or it can't be read.
+    pub fn read_only_keys(&self) -> Vec<String> {
+        file_keys(&self.policy_file())
+    }
+
+    /// Dotted keys set by the system-wide defaults.
+    ///
+    /// Empty if there are no system-wide defaults, or they can't be read.
+    pub(crate) fn default_keys(&self) -> Vec<String> {
+        file_keys(&self.defaults_file())
+    }
+}
+
+/// Dotted keys set by the YAML file at `path`.
+fn file_keys(path: &Path) -> Vec<String> {
+    let Ok(dict) = Figment::from(Yaml::file(path)).extract::<Dict>() else {
+        return Vec::new();
+    };
+    let mut keys = Vec::new();
+    collect_keys(&mut Vec::new(), &dict, &mut keys);
+    keys
+}
+
+/// Collect the dotted keys of the leaf values of `dict`.
+fn collect_keys(path: &mut Vec<String>, dict: &Dict, keys: &mut Vec<String>) {
+    for (key, value) in dict {
+        path.push(key.clone());
+        match value {
+            Value::Dict(_, inner) if !inner.is_empty() => collect_keys(path, inner, keys),
+            _ => keys.push(path.join(".")),
+        }
+        path.pop();
+    }
+}
@@ -153,3 +153,667 @@ fn test_init_honors_environment_and_profile_child() {
 
     assert_eq!(parsed_config.memos.port, Some(4242));
 }
+
+/// Test that invalid values are reset to their defaults and reported with their line.
+#[test]
+fn test_init_validated() {
+    static INVALID_YAML: &str = r#"
+memos:
+    mode: demo
+    port: not-a-port
+memospot:
+    remote:
+        url: ftp://example.com
+    updater:
+        check_interval: sometimes
+"#;
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let yaml_path = tmp_dir.path().join("memospot_invalid.yaml");
+    fs::write(&yaml_path, INVALID_YAML).unwrap();
+
+    let (config, errors) = Config::init_validated(&yaml_path).unwrap();
+    let default_config = Config::default();
+
+    assert_eq!(config.memos.mode, Some("demo".to_string()));
+    assert_eq!(config.memos.port, default_config.memos.port);
+    assert_eq!(
+        config.memospot.remote.url,
+        default_config.memospot.remote.url
+    );
+    assert_eq!(
+        config.memospot.updater.check_interval,
+        default_config.memospot.updater.check_interval
+    );
+
+    let found: Vec<(&str, Option<usize>)> =
+        errors.iter().map(|e| (e.path.as_str(), e.line)).collect();
+    assert_eq!(
+        found,
+        [
+            ("memos.port", Some(4)),
+            ("memospot.remote.url", Some(7)),
+            ("memospot.updater.check_interval", Some(9)),
+        ]
+    );
+}
+
+/// Test that a malformed section is reset as a whole.
+#[test]
+fn test_init_validated_malformed() {
+    static MALFORMED_YAML: &str = r#"
+memospot:
+mode: prod
+"#;
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let yaml_path = tmp_dir.path().join("memospot_malformed.yaml");
+    fs::write(&yaml_path, MALFORMED_YAML).unwrap();
+
+    let (config, errors) = Config::init_validated(&yaml_path).unwrap();
+
+    assert_eq!(config.memospot, Config::default().memospot);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, "memospot");
+    assert_eq!(errors[0].line, Some(2));
+}
+
+/// Test that the published JSON Schema is up to date.
+///
+/// Set `UPDATE_SCHEMA=1` to regenerate it.
+#[test]
+fn test_json_schema() {
+    let schema = Config::json_schema().unwrap();
+    let schema_path =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(Config::SCHEMA_FILE);
+    if env::var_os("UPDATE_SCHEMA").is_some() {
+        fs::write(&schema_path, &schema).unwrap();
+    }
+
+    let published = fs::read_to_string(&schema_path).unwrap_or_default();
+    assert!(
+        published == schema,
+        "{} is outdated. Run `UPDATE_SCHEMA=1 cargo test -p config` to regenerate it.",
+        Config::SCHEMA_FILE
+    );
+}
+
+/// Test that updating a file keeps comments, key order and unknown keys.
+#[tokio::test]
+async fn test_update_file() {
+    static USER_YAML: &str = r#"# My settings.
+memospot:
+    # Dark mode is easier on the eyes.
+    window:
+        theme: light # changed often
+        width: 1280
+    custom: keep me
+memos:
+    port: 5230
+"#;
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let yaml_path = tmp_dir.path().join("memospot.yaml");
+    fs::write(&yaml_path, USER_YAML).unwrap();
+
+    let previous = Config::init(&yaml_path).unwrap();
+    let mut config = previous.clone();
+    config.memospot.window.theme = Some("dark".to_string());
+    config.memospot.window.locale = Some("pt-BR".to_string());
+    config.memos.mode = Some("demo".to_string());
+    config.memos.env.vars = Some([("A".to_string(), "b".to_string())].into());
+    config.update_file(&previous, &yaml_path).await.unwrap();
+
+    let updated = fs::read_to_string(&yaml_path).unwrap();
+    assert_eq!(
+        updated,
+        r#"# My settings.
+memospot:
+    # Dark mode is easier on the eyes.
+    window:
+        theme: dark # changed often
+        width: 1280
+        locale: pt-BR
+    custom: keep me
+memos:
+    port: 5230
+    env:
+        vars:
+            A: b
+    mode: demo
+"#
+    );
+    assert_eq!(Config::init(&yaml_path).unwrap(), config);
+}
+
+/// Test that added keys follow the indentation of the document.
+#[tokio::test]
+async fn test_update_file_indentation() {
+    static USER_YAML: &str = "memospot:\n    window:\n        theme: light\n";
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let yaml_path = tmp_dir.path().join("memospot.yaml");
+    fs::write(&yaml_path, USER_YAML).unwrap();
+
+    let previous = Config::init(&yaml_path).unwrap();
+    let mut config = previous.clone();
+    config.memospot.diagnostics.redact_keys = Some(vec!["token".to_string()]);
+    config.memos.env.vars = Some([("A".to_string(), "b\n  c".to_string())].into());
+    config.update_file(&previous, &yaml_path).await.unwrap();
+
+    let updated = fs::read_to_string(&yaml_path).unwrap();
+    assert_eq!(
+        updated,
+        r#"memospot:
+    window:
+        theme: light
+    diagnostics:
+        redact_keys:
+        - token
+memos:
+    env:
+        vars:
+            A: |-
+                b
+                  c
+"#
+    );
+    assert_eq!(Config::init(&yaml_path).unwrap(), config);
+}
+
+/// Test that values that can't be updated in place are still saved.
+#[tokio::test]
+async fn test_update_file_fallback() {
+    static FLOW_YAML: &str = "memos: {port: 5230, env: {enabled: false}}\n";
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let yaml_path = tmp_dir.path().join("memospot.yaml");
+    fs::write(&yaml_path, FLOW_YAML).unwrap();
+
+    let previous = Config::init(&yaml_path).unwrap();
+    let mut config = previous.clone();
+    config.memos.port = Some(4321);
+    config.memospot.window.theme = Some("dark".to_string());
+    config.update_file(&previous, &yaml_path).await.unwrap();
+
+    assert_eq!(Config::init(&yaml_path).unwrap(), config);
+}
+
+/// Test that the fallback save leaves out values from the system-wide configuration.
+#[tokio::test]
+async fn test_update_file_fallback_without_system_values() {
+    use crate::SystemConfig;
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let system = SystemConfig::new(tmp_dir.path().join("system"));
+    fs::create_dir(tmp_dir.path().join("system")).unwrap();
+    fs::write(
+        system.defaults_file(),
+        "memos:\n  port: 4000\n  env:\n    vars:\n      PROXY: http://proxy\nmemospot:\n  window:\n    theme: dark\n",
+    )
+    .unwrap();
+    fs::write(
+        system.policy_file(),
+        "memospot:\n  remote:\n    url: https://memos.example.com\n",
+    )
+    .unwrap();
+    let yaml_path = tmp_dir.path().join("memospot.yaml");
+
+    let previous = Config::init_layered(&yaml_path, &system).unwrap();
+    let mut config = previous.clone();
+    config.memospot.window.theme = Some("light".to_string());
+    config
+        .update_file_layered(&previous, &yaml_path, &system)
+        .await
+        .unwrap();
+
+    let saved = fs::read_to_string(&yaml_path).unwrap();
+    assert!(!saved.contains("port:"));
+    assert!(!saved.contains("PROXY"));
+    assert!(!saved.contains("memos.example.com"));
+    assert!(saved.contains("theme: light"));
+    assert_eq!(Config::init_layered(&yaml_path, &system).unwrap(), config);
+}
+
+/// Test that migrations upgrade one version at a time, up to the current one.
+#[test]
+fn test_migrations_are_ordered() {
+    use crate::CONFIG_VERSION;
+    use crate::migration::Migrator;
+
+    let mut version = 0;
+    for migration in Migrator::migrations() {
+        assert_eq!(migration.source_version(), version);
+        assert_eq!(migration.target_version(), version + 1);
+        version = migration.target_version();
+    }
+    assert_eq!(version, CONFIG_VERSION);
+}
+
+/// Test the upgrade from Memospot v0.1.7, where `memos.env` was a plain map.
+#[test]
+fn test_migrate_v0_memos_env() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let yaml_path = tmp_dir.path().join("memospot.yaml");
+
+    fs::write(&yaml_path, "memos:\n  env:\n    MEMOS_METRIC: 'false'\n").unwrap();
+    let config = Config::init(&yaml_path).unwrap();
+    assert_eq!(config.config_version, Some(crate::CONFIG_VERSION));
+    assert_eq!(config.memos.env.enabled, Some(true));
+    assert_eq!(
+        config.memos.env.vars,
+        Some([("MEMOS_METRIC".to_string(), "false".to_string())].into())
+    );
+
+    fs::write(&yaml_path, "memos:\n  env: null\n").unwrap();
+    let config = Config::init(&yaml_path).unwrap();
+    assert_eq!(config.memos.env, Config::default().memos.env);
+
+    fs::write(&yaml_path, "memos:\n  env:\n    enabled: true\n").unwrap();
+    let config = Config::init(&yaml_path).unwrap();
+    assert_eq!(config.memos.env.enabled, Some(true));
+}
+
+/// Test that migrating a file keeps a backup of it, and is only done once.
+#[tokio::test]
+async fn test_migrate_file() {
+    static LEGACY_YAML: &str = "# Mine.\nmemos:\n  env:\n    MEMOS_METRIC: 'false'\n";
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let yaml_path = tmp_dir.path().join("memospot.yaml");
+    fs::write(&yaml_path, LEGACY_YAML).unwrap();
+
+    let backup = Config::migrate_file(&yaml_path).await.unwrap();
+    assert_eq!(backup, Some(tmp_dir.path().join("memospot.v0.yaml")));
+    assert_eq!(fs::read_to_string(backup.unwrap()).unwrap(), LEGACY_YAML);
+
+    let migrated = fs::read_to_string(&yaml_path).unwrap();
+    assert!(migrated.starts_with("# Mine.\n"));
+    let config = Config::parse_file(&yaml_path);
+    assert!(
+        config.is_err(),
+        "partial file must not parse without defaults"
+    );
+    let config = Config::init(&yaml_path).unwrap();
+    assert_eq!(config.config_version, Some(crate::CONFIG_VERSION));
+    assert_eq!(config.memos.env.enabled, Some(true));
+
+    assert_eq!(Config::migrate_file(&yaml_path).await.unwrap(), None);
+}
+
+/// Test that system-wide defaults are overridden by the user configuration,
+/// which is overridden by the system-wide policy.
+#[test]
+fn test_init_layered() {
+    use crate::SystemConfig;
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let system = SystemConfig::new(tmp_dir.path().join("system"));
+    fs::create_dir(tmp_dir.path().join("system")).unwrap();
+    fs::write(
+        system.defaults_file(),
+        "memos:\n  port: 4000\nmemospot:\n  window:\n    theme: dark\n",
+    )
+    .unwrap();
+    fs::write(
+        system.policy_file(),
+        "memospot:\n  remote:\n    url: https://memos.example.com\n  updater:\n    enabled: false\n",
+    )
+    .unwrap();
+    let yaml_path = tmp_dir.path().join("memospot.yaml");
+    fs::write(
+        &yaml_path,
+        "memos:\n  port: 4321\nmemospot:\n  updater:\n    enabled: true\n",
+    )
+    .unwrap();
+
+    let config = Config::init_layered(&yaml_path, &system).unwrap();
+    assert_eq!(config.memos.port, Some(4321));
+    assert_eq!(config.memospot.window.theme, Some("dark".to_string()));
+    assert_eq!(
+        config.memospot.remote.url,
+        Some("https://memos.example.com".to_string())
+    );
+    assert_eq!(config.memospot.updater.enabled, Some(false));
+
+    assert_eq!(
+        system.read_only_keys(),
+        ["memospot.remote.url", "memospot.updater.enabled"]
+    );
+    assert!(
+        SystemConfig::new(tmp_dir.path().join("missing"))
+            .read_only_keys()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_provenance() {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .arg("test_provenance_child")
+        .env("CONFIG_TEST_CHILD", "1")
+        .env("MEMOSPOT_MEMOS__ADDR", "0.0.0.0")
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "child test failed:\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_provenance_child() {
+    use crate::{Origin, SystemConfig};
+
+    if env::var_os("CONFIG_TEST_CHILD").is_none() {
+        return;
+    }
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let system = SystemConfig::new(tmp_dir.path());
+    fs::write(
+        system.defaults_file(),
+        "memospot:\n  window:\n    theme: dark\n",
+    )
+    .unwrap();
+    fs::write(
+        system.policy_file(),
+        "memospot:\n  updater:\n    enabled: false\n",
+    )
+    .unwrap();
+    let yaml_path = tmp_dir.path().join("memospot.yaml");
+    fs::write(&yaml_path, "memos:\n  port: 4321\n").unwrap();
+
+    let config = Config::init_layered(&yaml_path, &system).unwrap();
+    let provenance = config.provenance(&yaml_path, &system);
+    let origin = |key: &str| {
+        provenance
+            .iter()
+            .find(|p| p.key == key)
+            .map(|p| (p.origin.clone(), p.value.clone()))
+            .unwrap()
+    };
+
+    assert_eq!(
+        origin("memos.port"),
+        (Origin::File { path: yaml_path }, 4321.into())
+    );
+    assert_eq!(
+        origin("memos.addr"),
+        (
+            Origin::Env {
+                var: "MEMOSPOT_MEMOS__ADDR".into()
+            },
+            "0.0.0.0".into()
+        )
+    );
+    assert_eq!(
+        origin("memospot.window.theme"),
+        (
+            Origin::SystemDefaults {
+                path: system.defaults_file()
+            },
+            "dark".into()
+        )
+    );
+    assert_eq!(
+        origin("memospot.updater.enabled"),
+        (
+            Origin::Policy {
+                path: system.policy_file()
+            },
+            false.into()
+        )
+    );
+    assert_eq!(origin("memos.mode"), (Origin::Default, "prod".into()));
+}
+
+#[test]
+fn test_export_import() {
+    use crate::{ExportFormat, ExportOptions};
+
+    let mut config = Config::default();
+    config.memos.port = Some(4321);
+    config.memos.data = Some("/srv/memos".into());
+    config.memospot.window.width = Some(1280);
+    config.memospot.window.theme = Some("dark".into());
+    config.memospot.quick_capture.access_token = Some("secret-token".into());
+    config.memospot.env.vars = Some([("API_KEY".to_string(), "abc".to_string())].into());
+
+    let exported = config
+        .export(ExportFormat::Yaml, ExportOptions::default())
+        .unwrap();
+    assert!(exported.contains("port: 4321"));
+    assert!(exported.contains("theme: dark"));
+    for stripped in ["/srv/memos", "width", "secret-token", "API_KEY"] {
+        assert!(
+            !exported.contains(stripped),
+            "`{stripped}` should be stripped"
+        );
+    }
+
+    let kept = config
+        .export(
+            ExportFormat::Json,
+            ExportOptions {
+                strip_machine: false,
+                strip_secrets: false,
+            },
+        )
+        .unwrap();
+    assert_eq!(serde_json::from_str::<Config>(&kept).unwrap(), config);
+
+    // Values left out of the document are kept.
+    let mut target = Config::default();
+    target.memospot.window.width = Some(800);
+    let imported = target.import(&exported).unwrap();
+    assert_eq!(imported.memos.port, Some(4321));
+    assert_eq!(imported.memospot.window.theme, Some("dark".into()));
+    assert_eq!(imported.memospot.window.width, Some(800));
+    assert_eq!(imported.memos.data, None);
+
+    // JSON documents, too.
+    let imported = target.import(r#"{"memos": {"port": 1234}}"#).unwrap();
+    assert_eq!(imported.memos.port, Some(1234));
+
+    assert!(target.import("memos:\n  port: not-a-port\n").is_err());
+    assert!(target.import("memos:\n  limits:\n    nice: 100\n").is_err());
+}
+
+/// Test that every configuration field is classified as live or restart required.
+///
+/// Classify new fields with `#[schemars(extend("x-restart" = true))]`, or `false` if
+/// changes apply live.
+#[test]
+fn test_restart_classification() {
+    let classification = Config::restart_classification();
+    let unclassified: Vec<&str> = classification
+        .iter()
+        .filter(|(_, restart)| restart.is_none())
+        .map(|(key, _)| key.as_str())
+        .collect();
+    assert!(
+        unclassified.is_empty(),
+        "unclassified fields: {unclassified:?}"
+    );
+
+    // Leaves of the schema match the configuration.
+    let config = serde_json::to_value(Config::default()).unwrap();
+    for (key, _) in &classification {
+        let pointer: String = key.split('.').map(|part| format!("/{part}")).collect();
+        assert!(config.pointer(&pointer).is_some(), "`{key}` is not a field");
+    }
+
+    let before = Config::default();
+    let mut after = before.clone();
+    after.memospot.window.theme = Some("dark".to_string());
+    after.memospot.quick_capture.access_token = Some("token".to_string());
+    assert!(!before.restart_required(&after));
+    after.memos.limits.nice = Some(10);
+    assert!(before.restart_required(&after));
+}
+
+/// Test storing secrets in the encrypted vault, and resolving references to them.
+#[test]
+fn test_secret_vault() {
+    let key_dir = tempfile::tempdir().unwrap();
+    let output = Command::new(std::env::current_exe().unwrap())
+        .arg("test_secret_vault_child")
+        .env("CONFIG_TEST_CHILD", "1")
+        .env("MEMOSPOT_SECRETS_KEY_DIR", key_dir.path().join("keys"))
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "child test failed:\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_secret_vault_child() {
+    use crate::{SecretVault, resolve_secrets};
+    use std::collections::HashMap;
+
+    if env::var_os("CONFIG_TEST_CHILD").is_none() {
+        return;
+    }
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let cfg_path = tmp_dir.path().join("memospot.yaml");
+    let key_file = SecretVault::key_dir().join(SecretVault::KEY_FILE_NAME);
+    let legacy_key_file = tmp_dir.path().join(SecretVault::KEY_FILE_NAME);
+
+    let mut vault = SecretVault::open(&cfg_path).unwrap();
+    assert_eq!(vault.names().count(), 0);
+    vault.set("s3_key", "hunter2").unwrap();
+    vault.set("s3.secret-2", "correct horse").unwrap();
+    assert!(vault.set("bad name", "x").is_err());
+    assert!(vault.set("", "x").is_err());
+
+    // Values are encrypted, and can be read back.
+    let vault_file = tmp_dir.path().join(SecretVault::FILE_NAME);
+    let stored = fs::read_to_string(&vault_file).unwrap();
+    assert!(!stored.contains("hunter2"));
+    // The key is kept out of the data directory.
+    assert!(key_file.is_file());
+    assert!(!legacy_key_file.exists());
+    let mut vault = SecretVault::open(&cfg_path).unwrap();
+    assert_eq!(
+        vault.names().collect::<Vec<_>>(),
+        vec!["s3.secret-2", "s3_key"]
+    );
+    assert_eq!(vault.get("s3_key"), Some("hunter2"));
+    assert_eq!(vault.resolve("secret:s3_key").unwrap(), "hunter2");
+    assert_eq!(vault.resolve("plain").unwrap(), "plain");
+    assert!(vault.resolve("secret:missing").is_err());
+
+    assert!(vault.remove("s3.secret-2").unwrap());
+    assert!(!vault.remove("s3.secret-2").unwrap());
+    assert_eq!(SecretVault::open(&cfg_path).unwrap().names().count(), 1);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // Wider permissions are restricted on the next save.
+        for file in [&vault_file, &key_file] {
+            fs::set_permissions(file, fs::Permissions::from_mode(0o644)).unwrap();
+        }
+        SecretVault::open(&cfg_path)
+            .unwrap()
+            .set("s3_key", "hunter2")
+            .unwrap();
+        for file in [&vault_file, &key_file] {
+            assert_eq!(
+                fs::metadata(file).unwrap().permissions().mode() & 0o777,
+                0o600
+            );
+        }
+    }
+
+    let vars = HashMap::from([
+        ("S3_KEY".to_string(), "secret:s3_key".to_string()),
+        ("S3_OTHER".to_string(), "secret:missing".to_string()),
+        ("PLAIN".to_string(), "value".to_string()),
+    ]);
+    let (resolved, errors) = resolve_secrets(&vars, &cfg_path);
+    assert_eq!(resolved.get("S3_KEY").map(String::as_str), Some("hunter2"));
+    assert_eq!(resolved.get("PLAIN").map(String::as_str), Some("value"));
+    assert!(!resolved.contains_key("S3_OTHER"));
+    assert_eq!(errors.len(), 1);
+    assert!(format!("{:#}", errors[0]).contains("S3_OTHER"));
+
+    // A key left next to the configuration file by a previous version is moved.
+    fs::rename(&key_file, &legacy_key_file).unwrap();
+    let vault = SecretVault::open(&cfg_path).unwrap();
+    assert_eq!(vault.get("s3_key"), Some("hunter2"));
+    assert!(key_file.is_file());
+    assert!(!legacy_key_file.exists());
+
+    // A vault that can't be decrypted fails, instead of being replaced.
+    fs::write(&key_file, [0u8; 32]).unwrap();
+    assert!(SecretVault::open(&cfg_path).is_err());
+    let (resolved, errors) = resolve_secrets(&vars, &cfg_path);
+    assert_eq!(resolved.len(), 1);
+    assert_eq!(errors.len(), 2);
+}
+
+/// Test that environment variables are expanded in validated paths.
+#[test]
+fn test_validate_expands_variables() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let mut config = Config::default();
+    config.memos.data = Some(format!(
+        "${{MEMOSPOT_TEST_UNSET_VAR:-{}}}",
+        tmp_dir.path().display()
+    ));
+    config.memos.working_dir = Some("${MEMOSPOT_TEST_UNSET_VAR}/memos".to_string());
+
+    let errors = config.validate();
+    let found: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(found, ["memos.working_dir"]);
+    assert!(errors[0].message.contains("MEMOSPOT_TEST_UNSET_VAR"));
+}
+
+/// Test that secrets are redacted or removed, including in arrays.
+#[test]
+fn test_redact_secrets() {
+    use crate::{is_secret_key, redact_secrets};
+    use serde_json::json;
+
+    let patterns = crate::Diagnostics::default().redact_patterns();
+    assert!(is_secret_key("ACCESS_TOKEN", &patterns));
+    assert!(!is_secret_key("theme", &patterns));
+
+    let value = json!({
+        "access_token": "abc",
+        "redact_keys": ["token"],
+        "servers": [{"url": "https://memos", "password": "hunter2"}],
+    });
+
+    let mut redacted = value.clone();
+    redact_secrets(&mut redacted, &patterns, Some("[REDACTED]"));
+    assert_eq!(
+        redacted,
+        json!({
+            "access_token": "[REDACTED]",
+            "redact_keys": ["token"],
+            "servers": [{"url": "https://memos", "password": "[REDACTED]"}],
+        })
+    );
+
+    let mut stripped = value;
+    redact_secrets(&mut stripped, &patterns, None);
+    assert_eq!(
+        stripped,
+        json!({
+            "redact_keys": ["token"],
+            "servers": [{"url": "https://memos"}],
+        })
+    );
+}
@@ -0,0 +1,224 @@
+//! Configuration validation.
+//!
+//! [`Config::init_validated`] resets invalid values to their defaults, one at a time,
+//! instead of rejecting the whole file, and reports each of them with its YAML path and line.
+
+use crate::config::Config;
+use crate::document::find_line;
+use crate::system::SystemConfig;
+
+use anyhow::{Error, Result};
+use figment::Figment;
+use figment::providers::Serialized;
+use figment::value::{Dict, Value};
+use homedir::HomeDirExt;
+use std::fmt;
+use std::path::Path;
+
+/// Upper bound on reported errors, in case resetting a value doesn't fix it.
+const MAX_ERRORS: usize = 100;
+
+/// Memo visibilities accepted by Memos.
+const VISIBILITIES: [&str; 3] = ["PRIVATE", "PROTECTED", "PUBLIC"];
+
+/// An invalid configuration value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Dotted key path, like `memos.port`.
+    pub path: String,
+    /// Line of the key in the configuration file, if it's there.
+    pub line: Option<usize>,
+    /// What's wrong with the value.
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            line: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{} (line {line}): {}", self.path, self.message),
+            None => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+impl Config {
+    /// Initialize configuration from a file, resetting invalid values to their defaults.
+    ///
+    /// Returns the configuration along with the reset values. Only fails if the file
+    /// can't be read or isn't valid YAML.
+    pub fn init_validated(cfg_path: &Path) -> Result<(Config, Vec<ValidationError>), Error> {
+        let figment = Config::layers(cfg_path, &SystemConfig::locate());
+        let mut data: Dict = figment.extract()?;
+
+        let mut errors = Vec::new();
+        let config = loop {
+            let extracted = Figment::from(Serialized::defaults(&data))
+                .join(Serialized::defaults(Config::default()))
+                .extract::<Config>();
+
+            let invalid = match extracted {
+                Ok(config) => {
+                    let invalid = config.validate();
+                    if invalid.is_empty() {
+                        break config;
+                    }
+                    invalid
+                }
+                Err(e) => {
+                    let path = e.path.join(".");
+                    vec![ValidationError::new(&path, e.kind.to_string())]
+                }
+            };
+            for error in invalid {
+                let path: Vec<&str> = error.path.split('.').collect();
+                // Fall back to resetting the parent, if the value itself can't be removed.
+                let removed = (1..=path.len())
+                    .rev()
+                    .any(|n| remove(&mut data, &path[..n]));
+                if !removed || errors.len() >= MAX_ERRORS {
+                    anyhow::bail!("invalid configuration: {error}");
+                }
+                errors.push(error);
+            }
+        };
+
+        let yaml = std::fs::read_to_string(cfg_path).unwrap_or_default();
+        for error in &mut errors {
+            let path: Vec<&str> = error.path.split('.').collect();
+            error.line = find_line(&yaml, &path);
+        }
+        Ok((config, errors))
+    }
+
+    /// Check values that are well-typed, but still invalid.
+    ///
+    /// The returned errors have no line information.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let memos = &self.memos;
+        let memospot = &self.memospot;
+
+        check_path(
+            &mut errors,
+            "memos.binary_path",
+            &memos.binary_path,
+            PathKind::File,
+        );
+        check_path(&mut errors, "memos.data", &memos.data, PathKind::Dir);
+        check_path(
+            &mut errors,
+            "memos.working_dir",
+            &memos.working_dir,
+            PathKind::Dir,
+        );
+
+        if let Some(nice) = memos.limits.nice
+            && !(-20..=19).contains(&nice)
+        {
+            errors.push(ValidationError::new(
+                "memos.limits.nice",
+                "must be between -20 and 19",
+            ));
+        }
+
+        if let Some(url) = non_empty(&memospot.remote.url) {
+            match url::Url::parse(url) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                Ok(_) => errors.push(ValidationError::new(
+                    "memospot.remote.url",
+                    "must be an http or https URL",
+                )),
+                Err(e) => errors.push(ValidationError::new(
+                    "memospot.remote.url",
+                    format!("invalid URL: {e}"),
+                )),
+            }
+        }
+
+        if let Some(interval) = non_empty(&memospot.updater.check_interval)
+            && let Err(e) = interval.parse::<humantime::Duration>()
+        {
+            errors.push(ValidationError::new(
+                "memospot.updater.check_interval",
+                format!("invalid duration, like `3d` or `12h`: {e}"),
+            ));
+        }
+
+        if memospot.monitor.interval == Some(0) {
+            errors.push(ValidationError::new(
+                "memospot.monitor.interval",
+                "must be at least 1 second",
+            ));
+        }
+
+        if let Some(visibility) = non_empty(&memospot.quick_capture.visibility)
+            && !VISIBILITIES.contains(&visibility)
+        {
+            errors.push(ValidationError::new(
+                "memospot.quick_capture.visibility",
+                format!("must be one of {}", VISIBILITIES.join(", ")),
+            ));
+        }
+
+        errors
+    }
+}
+
+enum PathKind {
+    File,
+    Dir,
+}
+
+/// Check that a user-provided path exists. Empty paths and "." mean the default location.
+fn check_path(
+    errors: &mut Vec<ValidationError>,
+    key: &str,
+    path: &Option<String>,
+    kind: PathKind,
+) {
+    let Some(path) = non_empty(path).filter(|p| *p != ".") else {
+        return;
+    };
+    let expanded = match path.expand() {
+        Ok(expanded) => expanded,
+        Err(e) => {
+            errors.push(ValidationError::new(
+                key,
+                format!("unable to expand path: {e}"),
+            ));
+            return;
+        }
+    };
+    let message = match kind {
+        PathKind::File if !expanded.is_file() => "file not found",
+        PathKind::Dir if !expanded.is_dir() => "directory not found",
+        _ => return,
+    };
+    errors.push(ValidationError::new(key, format!("{message}: {path}")));
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Remove the value at `path`. Returns whether it was there.
+fn remove(dict: &mut Dict, path: &[&str]) -> bool {
+    match path {
+        [] => false,
+        [key] => dict.remove(*key).is_some(),
+        [key, rest @ ..] => match dict.get_mut(*key) {
+            Some(Value::Dict(_, inner)) => remove(inner, rest),
+            _ => false,
+        },
+    }
+}
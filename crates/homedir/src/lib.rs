@@ -1,4 +1,4 @@
-//! Enable expansion of tildes in paths
+//! Enable expansion of tildes and environment variables in paths
 //!
 //! Patched to work on Windows. Lacks resolving of `~user` on Windows.
 //!
@@ -7,15 +7,21 @@
 //!
 //! - ~`user` will expand to the user's home directory from the user database
 //!
+//! - `${VAR}` expands to the value of the environment variable `VAR`
+//! - `${VAR:-default}` expands to `default` if `VAR` is unset or empty
+//!
 //! Example:
 //!
 //! ```
 //! use homedir::HomeDirExt;
 //!
 //! let public_html = "~/public_html".expand_home().unwrap();
+//! let memos_data = "${XDG_DATA_HOME:-~/.local/share}/memos".expand().unwrap();
 //! ```
 
 use home::home_dir;
+use std::env;
+use std::ffi::OsString;
 use std::path::{Component, Path, PathBuf};
 
 #[cfg(not(target_os = "windows"))]
@@ -35,6 +41,14 @@ pub enum Error {
     /// Can't find name for current user.
     #[error("the current user (numeric id {0}) does not exist in the user database")]
     CurrentUserDoesNotExist(u32),
+
+    /// An environment variable referenced as `${VAR}` is not set
+    #[error("the environment variable {0} is not set")]
+    MissingVar(String),
+
+    /// A `${` is not closed by `}`
+    #[error("unterminated variable reference in {0}")]
+    UnterminatedVar(String),
 }
 
 /// The expansion trait extension
@@ -70,6 +84,37 @@ pub trait HomeDirExt {
     /// let root_home = "~root".expand_home().unwrap();
     /// ```
     fn expand_home(&self) -> Result<PathBuf, Error>;
+
+    /// Expands environment variables written as `${VAR}`, or `${VAR:-default}` to use
+    /// `default` if `VAR` is unset or empty.
+    ///
+    /// Examples:
+    /// ```
+    /// # use homedir::HomeDirExt;
+    /// # use std::path::PathBuf;
+    /// assert_eq!(
+    ///     "${MEMOSPOT_DOC_UNSET:-/srv}/memos".expand_vars().unwrap(),
+    ///     PathBuf::from("/srv/memos")
+    /// );
+    /// assert!("${MEMOSPOT_DOC_UNSET}/memos".expand_vars().is_err());
+    /// ```
+    fn expand_vars(&self) -> Result<PathBuf, Error>;
+
+    /// Expands environment variables, then the home directory.
+    ///
+    /// Examples:
+    /// ```
+    /// # use homedir::HomeDirExt;
+    /// # use std::env::var;
+    /// # use std::path::PathBuf;
+    /// let mut path = PathBuf::from(var("HOME").unwrap());
+    /// path.push(".local/share/memos");
+    ///
+    /// assert_eq!("${MEMOSPOT_DOC_UNSET:-~/.local/share}/memos".expand().unwrap(), path);
+    /// ```
+    fn expand(&self) -> Result<PathBuf, Error> {
+        self.expand_vars()?.expand_home()
+    }
 }
 
 impl HomeDirExt for Path {
@@ -106,6 +151,13 @@ impl HomeDirExt for Path {
 
         Ok(path)
     }
+
+    fn expand_vars(&self) -> Result<PathBuf, Error> {
+        match self.to_str() {
+            Some(s) if s.contains("${") => interpolate(s).map(PathBuf::from),
+            _ => Ok(self.to_path_buf()),
+        }
+    }
 }
 
 impl<T> HomeDirExt for T
@@ -115,6 +167,36 @@ where
     fn expand_home(&self) -> Result<PathBuf, Error> {
         self.as_ref().expand_home()
     }
+
+    fn expand_vars(&self) -> Result<PathBuf, Error> {
+        self.as_ref().expand_vars()
+    }
+}
+
+/// Replace `${VAR}` and `${VAR:-default}` in `input` with the environment variables.
+pub(crate) fn interpolate(input: &str) -> Result<OsString, Error> {
+    let mut output = OsString::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        output.push(&rest[..start]);
+        let reference = &rest[start + 2..];
+        let end = reference
+            .find('}')
+            .ok_or_else(|| Error::UnterminatedVar(input.to_string()))?;
+        let (name, default) = match reference[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&reference[..end], None),
+        };
+        let value = env::var_os(name).filter(|value| default.is_none() || !value.is_empty());
+        match (value, default) {
+            (Some(value), _) => output.push(value),
+            (None, Some(default)) => output.push(default),
+            (None, None) => return Err(Error::MissingVar(name.to_string())),
+        }
+        rest = &reference[end + 1..];
+    }
+    output.push(rest);
+    Ok(output)
 }
 
 pub(crate) fn getenv() -> Option<PathBuf> {
@@ -77,3 +77,62 @@ fn test_missing() {
         Error::MissingEntry(_)
     ));
 }
+
+/// Test that `${VAR}` and `${VAR:-default}` are replaced with environment variables.
+#[test]
+fn test_interpolate() {
+    unsafe {
+        env::set_var("HOMEDIR_TEST_DATA", "/srv/memos");
+        env::set_var("HOMEDIR_TEST_EMPTY", "");
+        env::remove_var("HOMEDIR_TEST_UNSET");
+    }
+
+    assert_eq!(
+        "${HOMEDIR_TEST_DATA}/assets".expand_vars().unwrap(),
+        PathBuf::from("/srv/memos/assets")
+    );
+    assert_eq!(
+        "${HOMEDIR_TEST_UNSET:-/opt/memos}/${HOMEDIR_TEST_DATA}"
+            .expand_vars()
+            .unwrap(),
+        PathBuf::from("/opt/memos//srv/memos")
+    );
+    // Empty values are replaced by the default, like in POSIX shells.
+    assert_eq!(
+        "${HOMEDIR_TEST_EMPTY:-fallback}".expand_vars().unwrap(),
+        PathBuf::from("fallback")
+    );
+    assert_eq!(
+        "prefix${HOMEDIR_TEST_EMPTY}".expand_vars().unwrap(),
+        PathBuf::from("prefix")
+    );
+    // Only braced references are expanded, as `$` is valid in paths.
+    assert_eq!(
+        "/data/$HOMEDIR_TEST_DATA".expand_vars().unwrap(),
+        PathBuf::from("/data/$HOMEDIR_TEST_DATA")
+    );
+
+    assert!(matches!(
+        "${HOMEDIR_TEST_UNSET}/memos".expand_vars().unwrap_err(),
+        Error::MissingVar(name) if name == "HOMEDIR_TEST_UNSET"
+    ));
+    assert!(matches!(
+        "${HOMEDIR_TEST_DATA/memos".expand_vars().unwrap_err(),
+        Error::UnterminatedVar(_)
+    ));
+}
+
+/// Test that variables are expanded before the home directory.
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn test_expand_vars_then_home() {
+    unsafe {
+        env::remove_var("HOMEDIR_TEST_XDG_UNSET");
+    }
+    assert_eq!(
+        "${HOMEDIR_TEST_XDG_UNSET:-~root}/.local/share"
+            .expand()
+            .unwrap(),
+        getent("root").unwrap().join(".local/share")
+    );
+}
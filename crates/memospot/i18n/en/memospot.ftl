@@ -4,6 +4,7 @@ appmenu-check-for-updates = &Check for updates…
 appmenu-open-in-browser = &Open in browser…
 appmenu-quit = &Quit
 appmenu-settings = &Settings
+capture-window-title = Quick capture
 dialog-generic-error = Error
 dialog-generic-info = Information
 dialog-update-failed-title = Auto-update failed
@@ -25,6 +26,9 @@ error-failed-to-install-webview = Failed to install WebView:
     { $error }
 
     Please install it manually.
+error-failed-to-restart-memos = Failed to restart Memos:
+
+    { $error }
 error-invalid-server-url = Invalid remote server URL:
     { $url }
 
@@ -32,10 +36,17 @@ error-invalid-server-url = Invalid remote server URL:
     Check the settings.
 helpmenu = &Help
 helpmenu-documentation = &Documentation
+helpmenu-export-diagnostics = &Export Diagnostics…
 helpmenu-memos-version = Memos version
 helpmenu-memospot-version = &Memospot version
 helpmenu-release-notes = &Release Notes
 helpmenu-report-issue = &Report an Issue…
+info-backup-completed = Database backup saved to:
+    { $file }
+info-diagnostics-exported = Diagnostics saved to:
+    { $file }
+
+    Review the contents before sharing.
 panic-backup-directory-is-a-file = Backup directory exists as a file!
     { $dir }
 panic-backup-directory-is-not-writable = Backup directory is not writable!
@@ -88,6 +99,9 @@ prompt-install-webview-message = A WebView is *required* for this application to
 
     Do you want to install it?
 prompt-install-webview-title = WebView Error
+traymenu-backup-now = &Backup now
+traymenu-restart-server = &Restart server
+traymenu-show-window = &Show Memospot
 viewmenu = &View
 viewmenu-developer-tools = &Developer Tools
 viewmenu-new-window = &New Window
@@ -99,5 +113,16 @@ viewmenu-zoom-in = Zoom &In
 viewmenu-zoom-out = Zoom &Out
 warn-failed-to-backup-database = Failed to backup database:
 
+    { $error }
+warn-failed-to-export-diagnostics = Failed to export diagnostics:
+
+    { $error }
+warn-config-invalid-values = Some configuration values are invalid and were reset to their defaults:
+
+    { $errors }
+
+    Fix them in { $file } to stop seeing this message.
+warn-failed-to-register-shortcut = Failed to register the quick-capture shortcut `{ $shortcut }`:
+
     { $error }
 windowmenu = &Window
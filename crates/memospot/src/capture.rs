@@ -0,0 +1,146 @@
+//! Quick capture.
+//!
+//! An OS-global hotkey opens a small, always-on-top window where a memo can be jotted
+//! down and posted to the active Memos server, without switching to the main window.
+//!
+//! The front-end lives at [`Route::Capture`] and calls [`crate::cmd::quick_capture`].
+
+use crate::route::Route;
+use crate::runtime_config::AppState;
+use crate::window::Window;
+use crate::{fl, menu};
+use anyhow::{Result, anyhow, bail};
+use config::{QuickCapture, SECRET_PREFIX, SecretVault};
+use dialog::warn_dialog;
+use log::{debug, info};
+use std::path::Path;
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindowBuilder, async_runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use tauri_plugin_http::reqwest;
+
+const TIMEOUT_MS: u64 = 5_000;
+
+/// Register the quick-capture hotkey, if enabled.
+///
+/// Requires the global shortcut plugin to be registered.
+pub fn setup<R: Runtime>(app: &AppHandle<R>) {
+    let config = app.state::<AppState>().config.snapshot().current;
+    let capture = &config.memospot.quick_capture;
+    if capture.enabled != Some(true) {
+        debug!("quick capture is disabled");
+        return;
+    }
+    let shortcut = capture.shortcut.clone().unwrap_or_default();
+
+    let registered = app
+        .global_shortcut()
+        .on_shortcut(shortcut.as_str(), |app, _, event| {
+            if event.state == ShortcutState::Pressed {
+                open_window(app);
+            }
+        });
+    match registered {
+        Ok(()) => info!("quick capture shortcut registered: {shortcut}"),
+        Err(e) => {
+            warn_dialog!(fl!(
+                "warn-failed-to-register-shortcut",
+                shortcut = shortcut,
+                error = e.to_string()
+            ));
+        }
+    }
+}
+
+/// Open the quick-capture window, or focus it if it's already open.
+pub fn open_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(Window::Capture.into()) {
+        window.show().ok();
+        window.set_focus().ok();
+        return;
+    }
+
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        let Ok(empty_menu) = menu::build_empty(&app) else {
+            return;
+        };
+        let new_window = WebviewWindowBuilder::new(
+            &app,
+            Window::Capture.to_string(),
+            WebviewUrl::App(Route::Capture.into()),
+        )
+        .title(fl!("capture-window-title"))
+        .center()
+        .inner_size(520.0, 260.0)
+        .min_inner_size(360.0, 200.0)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .disable_drag_drop_handler()
+        .visible(cfg!(debug_assertions))
+        .focused(true)
+        .menu(empty_menu);
+
+        #[cfg(not(target_os = "macos"))]
+        new_window.build().ok();
+        #[cfg(target_os = "macos")]
+        new_window
+            .title_bar_style(tauri::TitleBarStyle::Visible)
+            .build()
+            .ok();
+    });
+}
+
+/// Create a memo on the Memos server at `memos_url`.
+///
+/// See [`access_token`] for the token used.
+pub async fn create_memo(
+    memos_url: &str,
+    user_agent: &str,
+    settings: &QuickCapture,
+    config_file: &Path,
+    content: &str,
+) -> Result<()> {
+    if content.trim().is_empty() {
+        bail!("memo is empty");
+    }
+    let token = access_token(settings, config_file)?;
+    let visibility = settings.visibility.as_deref().unwrap_or("PRIVATE");
+
+    let endpoint = format!("{}/api/v1/memos", memos_url.trim_end_matches('/'));
+    let response = reqwest::Client::new()
+        .post(reqwest::Url::parse(&endpoint)?)
+        .header("User-Agent", user_agent)
+        .bearer_auth(token)
+        .json(&serde_json::json!({
+            "content": content,
+            "visibility": visibility,
+        }))
+        .timeout(std::time::Duration::from_millis(TIMEOUT_MS))
+        .send()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("server responded with status code {status}: {body}");
+    }
+    debug!("quick capture: memo created");
+    Ok(())
+}
+
+/// Access token from `settings`.
+///
+/// A reference like `secret:memos_token` is resolved from the vault of the configuration
+/// file `config_file`.
+pub fn access_token(settings: &QuickCapture, config_file: &Path) -> Result<String> {
+    let token = settings
+        .access_token
+        .as_deref()
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| anyhow!("no access token set in `memospot.quick_capture`"))?;
+    if !token.starts_with(SECRET_PREFIX) {
+        return Ok(token.to_string());
+    }
+    let vault = SecretVault::open(config_file)?;
+    Ok(vault.resolve(token)?.to_string())
+}
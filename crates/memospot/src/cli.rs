@@ -0,0 +1,962 @@
+//! Command-line interface for maintenance tasks.
+//!
+//! `memospot <command>` runs a single task against the same data directory,
+//! configuration and database the GUI uses, then exits without opening a window.
+//!
+//! Arguments that don't start with a known command, such as the ones passed by the
+//! OS on launch, are ignored here and the GUI starts as usual.
+
+use crate::history::ConfigHistory;
+#[cfg(debug_assertions)]
+use crate::runtime_config::apply_debug_overrides;
+use crate::runtime_config::{
+    ActiveServer, ConfigStore, RuntimeContext, RuntimePaths, json_pointer,
+};
+use crate::utils::get_app_data_path;
+use crate::{diagnostics, headless, init, memos, sqlite};
+use anyhow::{Context as _, Result, anyhow, bail};
+use config::{
+    Config, ExportFormat, ExportOptions, Origin, SECRET_PREFIX, SecretVault, SystemConfig,
+};
+use json_patch::{AddOperation, Patch, PatchOperation, ReplaceOperation};
+use migration::{DryRunReport, Migrator, MigratorTrait};
+use serde_json::{Value, json};
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use sysinfo::{Pid, System};
+use tauri::async_runtime;
+
+const USAGE: &str = "\
+Usage: memospot [COMMAND]
+
+Starts the Memospot app when no command is given.
+
+Commands:
+  backup [--output <DIR>]       Back up the database to the backup directory or <DIR>
+  restore <FILE>                Restore the database from a backup archive
+  migrate [--dry-run] [--json]  Run pending database migrations, or preview them
+  checkpoint                    Checkpoint the database write-ahead log
+  diagnostics [--output <DIR>]  Export a diagnostics bundle for bug reports to the current directory or <DIR>
+  config get [KEY]              Print the configuration, or a single value
+  config set <KEY> <VALUE>      Change a configuration value. VALUE is parsed as JSON, if possible
+  config origin [KEY]           Print where each configuration value, or a single one, comes from
+  config export [--json] [--keep-machine] [--keep-secrets]
+                                Print a portable configuration, without machine-specific values and secrets
+  config import <FILE> [--dry-run]
+                                Merge a configuration exported from another machine, or preview the changes
+  config history                List recorded configuration changes
+  config revert <ID>            Revert a recorded configuration change
+  config validate               Validate the configuration file
+  secret list                   List the names of stored secrets
+  secret set <NAME> [VALUE]     Store a secret. VALUE is read from standard input, if omitted
+  secret delete <NAME>          Delete a stored secret
+  server start                  Start the Memos server in the background
+  server stop                   Stop the Memos server
+  server status                 Print the Memos server status
+  --headless                    Run and supervise the Memos server without a window, until interrupted
+  help                          Print this message
+
+Configuration keys use dot notation, like `memos.port` or `memospot.backups.enabled`.
+Environment variable values can reference a stored secret as `secret:<NAME>`.
+";
+
+/// Exit code for `server status` when the server is not running (LSB convention).
+const EXIT_NOT_RUNNING: i32 = 3;
+
+/// A parsed command-line command.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Backup {
+        output: Option<PathBuf>,
+    },
+    Restore {
+        input: PathBuf,
+    },
+    Migrate {
+        dry_run: bool,
+        json: bool,
+    },
+    Checkpoint,
+    Diagnostics {
+        output: Option<PathBuf>,
+    },
+    ConfigGet {
+        key: Option<String>,
+    },
+    ConfigSet {
+        key: String,
+        value: String,
+    },
+    ConfigOrigin {
+        key: Option<String>,
+    },
+    ConfigExport {
+        json: bool,
+        keep_machine: bool,
+        keep_secrets: bool,
+    },
+    ConfigImport {
+        input: PathBuf,
+        dry_run: bool,
+    },
+    ConfigHistory,
+    ConfigRevert {
+        id: u64,
+    },
+    ConfigValidate,
+    SecretList,
+    SecretSet {
+        name: String,
+        value: Option<String>,
+    },
+    SecretDelete {
+        name: String,
+    },
+    ServerStart,
+    ServerStop,
+    ServerStatus,
+    Headless,
+}
+
+impl Command {
+    /// Parse command-line arguments, excluding the program name.
+    ///
+    /// Returns `None` if the arguments don't start with a known command.
+    pub fn parse<S: AsRef<str>>(args: &[S]) -> Option<Result<Self>> {
+        let args: Vec<&str> = args.iter().map(AsRef::as_ref).collect();
+        let (&name, rest) = args.split_first()?;
+        let command = match name {
+            "help" | "--help" | "-h" => no_args(rest).map(|_| Self::Help),
+            "backup" => parse_backup(rest),
+            "restore" => match rest {
+                [input] if !input.starts_with('-') => Ok(Self::Restore {
+                    input: PathBuf::from(input),
+                }),
+                _ => Err(anyhow!("usage: memospot restore <FILE>")),
+            },
+            "migrate" => parse_migrate(rest),
+            "checkpoint" => no_args(rest).map(|_| Self::Checkpoint),
+            "diagnostics" => parse_diagnostics(rest),
+            "config" => parse_config(rest),
+            "secret" => parse_secret(rest),
+            "server" => parse_server(rest),
+            "--headless" => no_args(rest).map(|_| Self::Headless),
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+fn no_args(args: &[&str]) -> Result<()> {
+    match args.first() {
+        Some(arg) => bail!("unexpected argument `{arg}`"),
+        None => Ok(()),
+    }
+}
+
+fn parse_backup(args: &[&str]) -> Result<Command> {
+    match args {
+        [] => Ok(Command::Backup { output: None }),
+        ["--output" | "-o", dir] => Ok(Command::Backup {
+            output: Some(PathBuf::from(dir)),
+        }),
+        _ => bail!("usage: memospot backup [--output <DIR>]"),
+    }
+}
+
+fn parse_diagnostics(args: &[&str]) -> Result<Command> {
+    match args {
+        [] => Ok(Command::Diagnostics { output: None }),
+        ["--output" | "-o", dir] => Ok(Command::Diagnostics {
+            output: Some(PathBuf::from(dir)),
+        }),
+        _ => bail!("usage: memospot diagnostics [--output <DIR>]"),
+    }
+}
+
+fn parse_migrate(args: &[&str]) -> Result<Command> {
+    let mut dry_run = false;
+    let mut json = false;
+    for arg in args {
+        match *arg {
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            _ => bail!("unexpected argument `{arg}`"),
+        }
+    }
+    if json && !dry_run {
+        bail!("`--json` requires `--dry-run`");
+    }
+    Ok(Command::Migrate { dry_run, json })
+}
+
+fn parse_config(args: &[&str]) -> Result<Command> {
+    match args {
+        ["get"] => Ok(Command::ConfigGet { key: None }),
+        ["get", key] => Ok(Command::ConfigGet {
+            key: Some(key.to_string()),
+        }),
+        ["set", key, value] => Ok(Command::ConfigSet {
+            key: key.to_string(),
+            value: value.to_string(),
+        }),
+        ["origin"] => Ok(Command::ConfigOrigin { key: None }),
+        ["origin", key] => Ok(Command::ConfigOrigin {
+            key: Some(key.to_string()),
+        }),
+        ["export", flags @ ..] => parse_config_export(flags),
+        ["import", input, flags @ ..] if !input.starts_with('-') => {
+            let dry_run = match flags {
+                [] => false,
+                ["--dry-run"] => true,
+                [arg, ..] => bail!("unexpected argument `{arg}`"),
+            };
+            Ok(Command::ConfigImport {
+                input: PathBuf::from(input),
+                dry_run,
+            })
+        }
+        ["history"] => Ok(Command::ConfigHistory),
+        ["revert", id] => match id.parse() {
+            Ok(id) => Ok(Command::ConfigRevert { id }),
+            Err(_) => bail!("invalid change id `{id}`. See `memospot config history`"),
+        },
+        ["validate"] => Ok(Command::ConfigValidate),
+        _ => bail!(
+            "usage: memospot config <get [KEY] | set <KEY> <VALUE> | origin [KEY] | export | import <FILE> | history | revert <ID> | validate>"
+        ),
+    }
+}
+
+fn parse_config_export(args: &[&str]) -> Result<Command> {
+    let mut json = false;
+    let mut keep_machine = false;
+    let mut keep_secrets = false;
+    for arg in args {
+        match *arg {
+            "--json" => json = true,
+            "--keep-machine" => keep_machine = true,
+            "--keep-secrets" => keep_secrets = true,
+            _ => bail!("unexpected argument `{arg}`"),
+        }
+    }
+    Ok(Command::ConfigExport {
+        json,
+        keep_machine,
+        keep_secrets,
+    })
+}
+
+fn parse_secret(args: &[&str]) -> Result<Command> {
+    match args {
+        ["list"] => Ok(Command::SecretList),
+        ["set", name] => Ok(Command::SecretSet {
+            name: name.to_string(),
+            value: None,
+        }),
+        ["set", name, value] => Ok(Command::SecretSet {
+            name: name.to_string(),
+            value: Some(value.to_string()),
+        }),
+        ["delete", name] => Ok(Command::SecretDelete {
+            name: name.to_string(),
+        }),
+        _ => bail!("usage: memospot secret <list | set <NAME> [VALUE] | delete <NAME>>"),
+    }
+}
+
+fn parse_server(args: &[&str]) -> Result<Command> {
+    match args {
+        ["start"] => Ok(Command::ServerStart),
+        ["stop"] => Ok(Command::ServerStop),
+        ["status"] => Ok(Command::ServerStatus),
+        _ => bail!("usage: memospot server <start | stop | status>"),
+    }
+}
+
+/// Run a command-line task, if one was requested.
+///
+/// Returns the process exit code, or `None` if the GUI should start instead.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = Command::parse(args)?;
+
+    #[cfg(windows)]
+    attach_console();
+
+    init::set_data_env(&get_app_data_path("memospot"));
+
+    let result = command.and_then(|command| match command {
+        Command::Help => {
+            print!("{USAGE}");
+            Ok(0)
+        }
+        Command::ConfigValidate => config_validate(),
+        Command::SecretList => secret_list(),
+        Command::SecretSet { name, value } => secret_set(&name, value),
+        Command::SecretDelete { name } => secret_delete(&name),
+        command => Context::load().and_then(|ctx| ctx.run(command)),
+    });
+
+    Some(result.unwrap_or_else(|e| {
+        eprintln!("error: {e:#}");
+        1
+    }))
+}
+
+/// Attach to the parent console, so output is visible on release builds.
+///
+/// Release builds use the Windows GUI subsystem, which starts without a console.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // SAFETY: `AttachConsole` has no preconditions. It fails harmlessly if
+    // there's no parent console or one is already attached.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+fn config_path() -> PathBuf {
+    get_app_data_path("memospot").join("memospot.yaml")
+}
+
+fn config_validate() -> Result<i32> {
+    let config_path = config_path();
+    if !config_path.is_file() {
+        bail!("configuration file not found: {}", config_path.display());
+    }
+    match Config::init_validated(&config_path) {
+        Ok((_, errors)) if errors.is_empty() => {
+            println!("{}: OK", config_path.display());
+            Ok(0)
+        }
+        Ok((_, errors)) => {
+            for error in errors {
+                eprintln!("{}: {error}", config_path.display());
+            }
+            Ok(1)
+        }
+        Err(e) => {
+            eprintln!("{}: {e}", config_path.display());
+            Ok(1)
+        }
+    }
+}
+
+fn secret_list() -> Result<i32> {
+    let vault = SecretVault::open(&config_path())?;
+    let mut names = vault.names().peekable();
+    if names.peek().is_none() {
+        println!("No secrets stored");
+    }
+    for name in names {
+        println!("{name}");
+    }
+    Ok(0)
+}
+
+fn secret_set(name: &str, value: Option<String>) -> Result<i32> {
+    let config_path = config_path();
+    if let Some(dir) = config_path.parent().filter(|dir| !dir.is_dir()) {
+        bail!(
+            "data directory not found: {}. Start Memospot once to create it",
+            dir.display()
+        );
+    }
+
+    // Reading from standard input keeps the value out of the shell history.
+    let value = match value {
+        Some(value) => value,
+        None => {
+            let mut value = String::new();
+            io::stdin().read_to_string(&mut value)?;
+            value.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+    if value.is_empty() {
+        bail!("the secret value is empty");
+    }
+
+    SecretVault::open(&config_path)?.set(name, &value)?;
+    println!("Secret `{name}` stored. Reference it as `{SECRET_PREFIX}{name}`");
+    Ok(0)
+}
+
+fn secret_delete(name: &str) -> Result<i32> {
+    if !SecretVault::open(&config_path())?.remove(name)? {
+        bail!("secret `{name}` not found");
+    }
+    println!("Secret `{name}` deleted");
+    Ok(0)
+}
+
+/// Paths and settings resolved the same way as on GUI startup, without side effects.
+pub(crate) struct Context {
+    pub(crate) config: Config,
+    pub(crate) runtime: RuntimeContext,
+}
+
+impl Context {
+    fn load() -> Result<Self> {
+        let memospot_data = get_app_data_path("memospot");
+        if !memospot_data.is_dir() {
+            bail!(
+                "data directory not found: {}. Start Memospot once to create it",
+                memospot_data.display()
+            );
+        }
+
+        let config_path = memospot_data.join("memospot.yaml");
+        let mut config = Config::init(&config_path).context("invalid configuration")?;
+        memos::sync_mode_demo_compat(&mut config.memos);
+
+        let mut effective_memos = config.memos.clone();
+        #[cfg(debug_assertions)]
+        apply_debug_overrides(&mut effective_memos);
+
+        let memos_data =
+            init::resolve_memos_data(&effective_memos, &memospot_data).map_err(|path| {
+                anyhow!(
+                    "unable to resolve custom data directory: {}",
+                    path.display()
+                )
+            })?;
+        let memos_db_file = init::database_path(&effective_memos, &memos_data);
+
+        let memospot_bin = env::current_exe()?;
+        let memospot_cwd = memospot_bin
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let memos_bin = init::locate_memos(&effective_memos, &memospot_data, &memospot_cwd)
+            .unwrap_or_default();
+
+        let remote = &config.memospot.remote;
+        let remote_url = remote.url.as_deref().unwrap_or_default();
+        let managed = remote.enabled != Some(true) || remote_url.is_empty();
+        let url = if managed {
+            memos::get_url(&config, effective_memos.port.unwrap_or_default())
+        } else {
+            format!("{}/", remote_url.trim_end_matches('/'))
+        };
+
+        let runtime = RuntimeContext {
+            paths: RuntimePaths {
+                memos_bin,
+                memos_data,
+                memos_db_file,
+                memospot_bin,
+                memospot_config_file: config_path,
+                memospot_cwd,
+                memospot_data,
+            },
+            active_server: ActiveServer {
+                url,
+                user_agent: format!("Memospot/{}", env!("CARGO_PKG_VERSION")),
+                managed,
+            },
+            memos: effective_memos,
+        };
+        Ok(Self { config, runtime })
+    }
+
+    fn run(self, command: Command) -> Result<i32> {
+        match command {
+            Command::Backup { output } => self.backup(output),
+            Command::Restore { input } => self.restore(&input),
+            Command::Migrate {
+                dry_run: true,
+                json,
+            } => self.migrate_dry_run(json),
+            Command::Migrate { dry_run: false, .. } => self.migrate().map(|_| 0),
+            Command::Checkpoint => self.checkpoint(),
+            Command::Diagnostics { output } => self.diagnostics(output),
+            Command::ConfigGet { key } => self.config_get(key.as_deref()),
+            Command::ConfigSet { key, value } => self.config_set(&key, &value),
+            Command::ConfigOrigin { key } => self.config_origin(key.as_deref()),
+            Command::ConfigExport {
+                json,
+                keep_machine,
+                keep_secrets,
+            } => self.config_export(json, keep_machine, keep_secrets),
+            Command::ConfigImport { input, dry_run } => self.config_import(&input, dry_run),
+            Command::ConfigHistory => self.config_history(),
+            Command::ConfigRevert { id } => self.config_revert(id),
+            Command::ServerStart => self.server_start(),
+            Command::ServerStop => self.server_stop(),
+            Command::ServerStatus => self.server_status(),
+            Command::Headless => headless::run(self),
+            Command::Help
+            | Command::ConfigValidate
+            | Command::SecretList
+            | Command::SecretSet { .. }
+            | Command::SecretDelete { .. } => unreachable!("handled before loading"),
+        }
+    }
+
+    fn db_file(&self) -> Result<&Path> {
+        let db_file = self.runtime.paths.memos_db_file.as_path();
+        if !db_file.is_file() {
+            bail!("database not found: {}", db_file.display());
+        }
+        Ok(db_file)
+    }
+
+    /// PID of the running Memos server, if any.
+    ///
+    /// Covers servers started by both the GUI and the command line.
+    pub(crate) fn running_pid(&self) -> Option<u32> {
+        let pid = memos::get_last_pid(&self.runtime.paths.memospot_data)?;
+        let sys = System::new_all();
+        sys.process(Pid::from_u32(pid)).map(|_| pid)
+    }
+
+    fn ensure_server_stopped(&self) -> Result<()> {
+        if let Some(pid) = self.running_pid() {
+            bail!(
+                "Memos server is running (PID {pid}). Close Memospot or run `memospot server stop` first"
+            );
+        }
+        Ok(())
+    }
+
+    fn backup_dir(&self, output: Option<PathBuf>) -> Result<PathBuf> {
+        let dir = match output {
+            Some(dir) => dir,
+            None => init::backup_directory(&self.config, &self.runtime.paths.memospot_data),
+        };
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("unable to create backup directory {}", dir.display()))?;
+        Ok(dir)
+    }
+
+    fn backup(&self, output: Option<PathBuf>) -> Result<i32> {
+        let db_file = self.db_file()?;
+        let backup_dir = self.backup_dir(output)?;
+        let backup = async_runtime::block_on(sqlite::backup(db_file, &backup_dir, "manual"))?;
+        println!("Backup saved to {}", backup.display());
+        Ok(0)
+    }
+
+    fn restore(&self, input: &Path) -> Result<i32> {
+        if !input.is_file() {
+            bail!("backup file not found: {}", input.display());
+        }
+        self.ensure_server_stopped()?;
+
+        let db_file = &self.runtime.paths.memos_db_file;
+        async_runtime::block_on(async {
+            if db_file.is_file() {
+                let backup_dir = self.backup_dir(None)?;
+                let backup = sqlite::backup(db_file, &backup_dir, "pre-restore").await?;
+                println!("Current database saved to {}", backup.display());
+            }
+            sqlite::restore(input, db_file).await
+        })?;
+        println!("Database restored from {}", input.display());
+        Ok(0)
+    }
+
+    /// Run pending migrations, backing up the database first if backups are enabled.
+    ///
+    /// Returns the amount of applied migrations.
+    fn migrate(&self) -> Result<usize> {
+        let db_file = self.db_file()?;
+        self.ensure_server_stopped()?;
+
+        async_runtime::block_on(async {
+            let db = sqlite::get_database_connection(db_file).await?;
+            let pending = Migrator::get_pending_migrations(&db).await?.len();
+            if pending == 0 {
+                db.close().await?;
+                println!("No pending migrations");
+                return anyhow::Ok(0);
+            }
+
+            if self.config.memospot.backups.enabled.unwrap_or_default() {
+                let backup_dir = self.backup_dir(None)?;
+                let backup = sqlite::backup(db_file, &backup_dir, "pre-migration").await?;
+                println!("Database saved to {}", backup.display());
+            }
+
+            Migrator::up(&db, None).await?;
+            db.close().await?;
+            println!("Applied {pending} migrations");
+            anyhow::Ok(pending)
+        })
+    }
+
+    fn migrate_dry_run(&self, json: bool) -> Result<i32> {
+        let db_file = self.db_file()?;
+        let report = async_runtime::block_on(migration::dry_run::<Migrator>(db_file))?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print_dry_run(&report);
+        }
+        Ok(0)
+    }
+
+    fn checkpoint(&self) -> Result<i32> {
+        let db_file = self.db_file()?;
+        async_runtime::block_on(sqlite::checkpoint(db_file))?;
+        println!("Checkpoint completed");
+        Ok(0)
+    }
+
+    fn diagnostics(&self, output: Option<PathBuf>) -> Result<i32> {
+        let output_dir = match output {
+            Some(dir) => dir,
+            None => env::current_dir()?,
+        };
+        let bundle = async_runtime::block_on(diagnostics::export(
+            &self.runtime,
+            &self.config,
+            &output_dir,
+        ))?;
+        println!("Diagnostics saved to {}", bundle.display());
+        println!("Review the contents before sharing.");
+        Ok(0)
+    }
+
+    fn config_get(&self, key: Option<&str>) -> Result<i32> {
+        let Some(key) = key else {
+            print!("{}", self.config.to_string()?);
+            return Ok(0);
+        };
+        let value = serde_json::to_value(&self.config)?;
+        match value.pointer(&json_pointer(key)) {
+            Some(Value::String(s)) => println!("{s}"),
+            Some(Value::Null) => println!(),
+            Some(v) => println!("{}", serde_json::to_string_pretty(v)?),
+            None => bail!("unknown configuration key `{key}`"),
+        }
+        Ok(0)
+    }
+
+    fn config_set(&self, key: &str, value: &str) -> Result<i32> {
+        let pointer = json_pointer(key);
+        if serde_json::to_value(&self.config)?
+            .pointer(&pointer)
+            .is_none()
+        {
+            bail!("unknown configuration key `{key}`");
+        }
+        if let Some(read_only) = read_only_key(key, &SystemConfig::locate().read_only_keys()) {
+            bail!("`{read_only}` is set by the system policy and can't be changed");
+        }
+        let value: Value =
+            serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+        let patch: Patch = serde_json::from_value(
+            json!([{ "op": "replace", "path": pointer, "value": value }]),
+        )?;
+
+        let update =
+            async_runtime::block_on(self.config_store().apply_patch_and_persist(&patch))?;
+        if update.result.restart_required {
+            println!("Configuration saved. Restart Memospot to apply the change");
+        } else {
+            println!("Configuration saved");
+        }
+        Ok(0)
+    }
+
+    fn config_origin(&self, key: Option<&str>) -> Result<i32> {
+        let provenance = self.config.provenance(
+            &self.runtime.paths.memospot_config_file,
+            &SystemConfig::locate(),
+        );
+        let prefix = key.map(|key| format!("{key}."));
+        let mut found = false;
+        for entry in provenance.iter().filter(|entry| match (key, &prefix) {
+            (Some(key), Some(prefix)) => entry.key == key || entry.key.starts_with(prefix),
+            _ => true,
+        }) {
+            found = true;
+            let origin = match &entry.origin {
+                Origin::Default => "default".to_string(),
+                Origin::SystemDefaults { path } => {
+                    format!("system defaults {}", path.display())
+                }
+                Origin::File { path } => format!("file {}", path.display()),
+                Origin::Env { var } => format!("environment variable {var}"),
+                Origin::Policy { path } => format!("system policy {}", path.display()),
+            };
+            println!(
+                "{}: {} ({origin}, profile {})",
+                entry.key, entry.value, entry.profile
+            );
+        }
+        if !found && let Some(key) = key {
+            bail!("unknown configuration key `{key}`");
+        }
+        Ok(0)
+    }
+
+    fn config_export(&self, json: bool, keep_machine: bool, keep_secrets: bool) -> Result<i32> {
+        let format = if json {
+            ExportFormat::Json
+        } else {
+            ExportFormat::Yaml
+        };
+        let options = ExportOptions {
+            strip_machine: !keep_machine,
+            strip_secrets: !keep_secrets,
+        };
+        print!("{}", self.config.export(format, options)?);
+        Ok(0)
+    }
+
+    fn config_import(&self, input: &Path, dry_run: bool) -> Result<i32> {
+        let document = fs::read_to_string(input)
+            .with_context(|| format!("unable to read {}", input.display()))?;
+        let import = async_runtime::block_on(self.config_store().import(&document, dry_run))?;
+
+        let result = import.result;
+        if result.patch.is_empty() {
+            println!("No changes");
+            return Ok(0);
+        }
+        for op in result.patch.iter() {
+            let key = dotted_key(op.path().as_str());
+            match op {
+                PatchOperation::Add(AddOperation { value, .. })
+                | PatchOperation::Replace(ReplaceOperation { value, .. }) => {
+                    println!("{key}: {value}")
+                }
+                _ => println!("{key}: (removed)"),
+            }
+        }
+        if !result.applied {
+            println!("Dry run: no changes saved");
+        } else if result.restart_required {
+            println!("Configuration imported. Restart Memospot to apply the changes");
+        } else {
+            println!("Configuration imported");
+        }
+        Ok(0)
+    }
+
+    fn config_history(&self) -> Result<i32> {
+        let history = ConfigHistory::load(&self.runtime.paths.memospot_config_file);
+        let mut changes = history.changes().peekable();
+        if changes.peek().is_none() {
+            println!("No configuration changes recorded");
+        }
+        for change in changes {
+            let time = chrono::DateTime::from_timestamp_millis(change.timestamp as i64)
+                .map(|time| {
+                    let time = time.with_timezone(&chrono::Local);
+                    time.format("%Y-%m-%d %H:%M:%S").to_string()
+                })
+                .unwrap_or_default();
+            let keys: Vec<String> = change
+                .patch
+                .iter()
+                .map(|op| dotted_key(op.path().as_str()))
+                .collect();
+            println!("{:>4}  {time}  {}", change.id, keys.join(", "));
+        }
+        Ok(0)
+    }
+
+    fn config_revert(&self, id: u64) -> Result<i32> {
+        let update = async_runtime::block_on(self.config_store().revert(id))?;
+        if update.result.restart_required {
+            println!("Change {id} reverted. Restart Memospot to apply it");
+        } else {
+            println!("Change {id} reverted");
+        }
+        Ok(0)
+    }
+
+    /// Configuration store for changes made from the command line, so they're checked
+    /// against the system policy and recorded in the history, like changes from the app.
+    fn config_store(&self) -> ConfigStore {
+        let config_file = self.runtime.paths.memospot_config_file.clone();
+        ConfigStore::new(self.config.clone(), self.config.clone(), config_file)
+            .with_read_only(SystemConfig::locate().read_only_keys())
+    }
+
+    /// Prepare to start a managed server: resolve a free port and run pending migrations.
+    pub(crate) fn prepare_server(&mut self) -> Result<()> {
+        if !self.runtime.active_server.managed {
+            bail!(
+                "a remote server is configured at {}. Nothing to start",
+                self.runtime.active_server.url
+            );
+        }
+        if !self.runtime.paths.memos_bin.is_file() {
+            bail!("unable to find Memos server binary");
+        }
+
+        let preferred_port = self.runtime.memos.port.unwrap_or_default();
+        let port = portpicker::find_free_port(preferred_port)
+            .ok_or_else(|| anyhow!("unable to find a free port"))?;
+        if port != preferred_port {
+            println!("Port {preferred_port} is in use. Using port {port}");
+            self.runtime.memos.port = Some(port);
+            self.runtime.active_server.url = memos::get_url(&self.config, port);
+            // Persist the resolved port, like the GUI does. In debug builds the
+            // port override is runtime-only and must not reach the configuration file.
+            #[cfg(not(debug_assertions))]
+            {
+                let mut config = self.config.clone();
+                config.memos.port = Some(port);
+                let config_path = &self.runtime.paths.memospot_config_file;
+                async_runtime::block_on(config.update_file(&self.config, config_path))?;
+            }
+        }
+
+        if self.runtime.paths.memos_db_file.is_file()
+            && self.config.memospot.migrations.enabled.unwrap_or_default()
+        {
+            self.migrate()?;
+        }
+        Ok(())
+    }
+
+    /// Start the Memos server as a detached process.
+    ///
+    /// The server is tracked through the same PID file as the GUI, so it can be
+    /// stopped from the command line and is recovered as an orphan by the GUI.
+    #[allow(clippy::zombie_processes)] // The child outlives this process by design.
+    fn server_start(mut self) -> Result<i32> {
+        if let Some(pid) = self.running_pid() {
+            bail!("Memos server is already running (PID {pid})");
+        }
+        self.prepare_server()?;
+
+        let paths = &self.runtime.paths;
+        let log = if self.config.memospot.log.enabled.unwrap_or_default() {
+            let file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(paths.memospot_data.join("memos.log"))?;
+            (Stdio::from(file.try_clone()?), Stdio::from(file))
+        } else {
+            (Stdio::null(), Stdio::null())
+        };
+
+        let mut command = std::process::Command::new(&paths.memos_bin);
+        command
+            .envs(memos::prepare_env(&self.runtime))
+            .current_dir(memos::get_cwd(&self.runtime))
+            .stdin(Stdio::null())
+            .stdout(log.0)
+            .stderr(log.1);
+        // Detach from the terminal, so Ctrl+C in the shell doesn't reach the server.
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            command.creation_flags(CREATE_NEW_PROCESS_GROUP | CREATE_NO_WINDOW);
+        }
+
+        let child = command.spawn().context("failed to spawn Memos server")?;
+        let pid = child.id();
+        fs::write(paths.memospot_data.join("memos.pid"), pid.to_string())?;
+
+        let url = &self.runtime.active_server.url;
+        async_runtime::block_on(memos::wait_api_ready(url));
+        match async_runtime::block_on(memos::query_version(url)) {
+            Ok(version) => println!("Memos v{version} running at {url} (PID {pid})"),
+            Err(e) => bail!("Memos server started (PID {pid}), but is not responding: {e}"),
+        }
+        Ok(0)
+    }
+
+    fn server_stop(&self) -> Result<i32> {
+        let Some(pid) = self.running_pid() else {
+            println!("Memos server is not running");
+            return Ok(0);
+        };
+        async_runtime::block_on(memos::shutdown(&self.runtime));
+        println!("Memos server stopped (PID {pid})");
+        Ok(0)
+    }
+
+    fn server_status(&self) -> Result<i32> {
+        let url = &self.runtime.active_server.url;
+        let version = async_runtime::block_on(memos::query_version(url)).ok();
+        let pid = self.running_pid();
+
+        let (status, exit_code) = match (&version, pid, self.runtime.active_server.managed) {
+            (Some(_), _, false) => ("remote server is reachable", 0),
+            (None, _, false) => ("remote server is unreachable", EXIT_NOT_RUNNING),
+            (Some(_), _, true) => ("running", 0),
+            (None, Some(_), true) => ("running, but not responding", 1),
+            (None, None, true) => ("not running", EXIT_NOT_RUNNING),
+        };
+
+        println!("Status:  {status}");
+        println!("URL:     {url}");
+        if let Some(pid) = pid {
+            println!("PID:     {pid}");
+        }
+        if let Some(version) = version.filter(|v| !v.is_empty()) {
+            println!("Version: {version}");
+        }
+        println!("Data:    {}", self.runtime.paths.memos_data.display());
+        Ok(exit_code)
+    }
+}
+
+/// Convert a JSON pointer, like `/memos/port`, to a dotted configuration key.
+fn dotted_key(pointer: &str) -> String {
+    pointer
+        .trim_start_matches('/')
+        .split('/')
+        .map(|part| part.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The key set by the system policy that `key` is, contains or belongs to, if any.
+fn read_only_key<'a>(key: &str, read_only: &'a [String]) -> Option<&'a String> {
+    let prefix = format!("{key}.");
+    read_only
+        .iter()
+        .find(|k| *k == key || k.starts_with(&prefix) || key.starts_with(&format!("{k}.")))
+}
+
+fn print_dry_run(report: &DryRunReport) {
+    if report.migrations.is_empty() {
+        println!("No pending migrations");
+        return;
+    }
+    for migration in &report.migrations {
+        println!("{}", migration.name);
+        if migration.is_empty() {
+            println!("  no changes");
+        }
+        for change in &migration.resources {
+            let before = change.before.as_deref().unwrap_or("<none>");
+            let after = change.after.as_deref().unwrap_or("<removed>");
+            println!("  resource {}: {before} -> {after}", change.id);
+        }
+        for change in &migration.settings {
+            let before = change.before.as_deref().unwrap_or("<none>");
+            let after = change.after.as_deref().unwrap_or("<removed>");
+            println!("  setting {}: {before} -> {after}", change.name);
+        }
+    }
+    println!(
+        "{} pending migrations would change {} rows",
+        report.migrations.len(),
+        report.len()
+    );
+}
@@ -5,15 +5,18 @@
 //!
 //! The TypeScript/JavaScript API is defined in `src-ui/src/lib/tauri.ts`.
 
-use crate::runtime_config::{AppState, ConfigUpdateResult};
-use crate::{i18n, memos, menu};
-use config::Config;
+use crate::history::ConfigChange;
+use crate::monitor::{Monitor, ResourceSample};
+use crate::runtime_config::{AppState, ConfigImportResult, ConfigUpdateResult};
+use crate::{capture, i18n, memos, menu};
+use config::{Config, ExportFormat, ExportOptions, Provenance, SecretVault, SystemConfig};
 use i18n_embed::LanguageLoader;
 use json_patch::Patch;
 use log::{debug, error};
+use migration::{DryRunReport, Migrator};
 use tauri::{AppHandle, Runtime, State, command};
 
-fn apply_locale<R: Runtime>(app: &AppHandle<R>, state: &AppState) {
+pub(crate) fn apply_locale<R: Runtime>(app: &AppHandle<R>, state: &AppState) {
     let current_locale = state
         .config
         .snapshot()
@@ -132,10 +135,15 @@ pub async fn get_env(name: &str) -> Result<String, String> {
 }
 
 /// Get the current app config.
+///
+/// `read_only` lists the dotted keys set by the system policy.
 #[command]
 pub async fn get_config(state: State<'_, AppState>) -> Result<String, String> {
     let config = state.config.snapshot();
-    let serialized = match serde_json::to_string(&*config.current) {
+    let serialized = match serde_json::to_value(&*config.current).and_then(|mut value| {
+        value["read_only"] = state.config.read_only_keys().into();
+        serde_json::to_string(&value)
+    }) {
         Ok(s) => s,
         Err(e) => {
             error!("failed to serialize config: {e}");
@@ -145,6 +153,18 @@ pub async fn get_config(state: State<'_, AppState>) -> Result<String, String> {
     Ok(serialized)
 }
 
+/// Get the effective value of each configuration key, and the layer that set it.
+#[command]
+pub async fn get_config_provenance(
+    state: State<'_, AppState>,
+) -> Result<Vec<Provenance>, String> {
+    let config = state.config.snapshot();
+    Ok(config.current.provenance(
+        &state.runtime.paths.memospot_config_file,
+        &SystemConfig::locate(),
+    ))
+}
+
 /// Get the default app config.
 #[command]
 pub async fn get_default_config() -> Result<String, String> {
@@ -200,6 +220,161 @@ pub async fn set_config<R: Runtime>(
     Ok(update.result)
 }
 
+/// Export a portable configuration document, as YAML or JSON.
+///
+/// Machine-specific values and secrets are optionally left out.
+#[command]
+pub async fn export_config(
+    state: State<'_, AppState>,
+    json: bool,
+    strip_machine: bool,
+    strip_secrets: bool,
+) -> Result<String, String> {
+    let format = if json {
+        ExportFormat::Json
+    } else {
+        ExportFormat::Yaml
+    };
+    let options = ExportOptions {
+        strip_machine,
+        strip_secrets,
+    };
+    state
+        .config
+        .snapshot()
+        .current
+        .export(format, options)
+        .map_err(|e| format!("failed to export configuration: {e}"))
+}
+
+/// Import a configuration document exported by `export_config`.
+///
+/// The document is validated and diffed against the current configuration. With
+/// `dry_run`, the changes are only reported. Otherwise, they're persisted through the
+/// managed configuration store.
+#[command]
+pub async fn import_config<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    document: String,
+    dry_run: bool,
+) -> Result<ConfigImportResult, String> {
+    let import = state
+        .config
+        .import(&document, dry_run)
+        .await
+        .map_err(|e| e.to_string())?;
+    debug!(
+        "configuration import: {} change(s), applied: {}",
+        import.result.patch.len(),
+        import.result.applied
+    );
+
+    if import.locale_changed {
+        apply_locale(&app, &state);
+    }
+
+    Ok(import.result)
+}
+
+/// List recorded configuration changes, oldest first.
+#[command]
+pub async fn get_config_history(
+    state: State<'_, AppState>,
+) -> Result<Vec<ConfigChange>, String> {
+    Ok(state.config.history().await)
+}
+
+/// Revert a recorded configuration change.
+///
+/// The revert is recorded as a change too.
+#[command]
+pub async fn revert_config_change<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    id: u64,
+) -> Result<ConfigUpdateResult, String> {
+    let update = state.config.revert(id).await.map_err(|e| e.to_string())?;
+    debug!(
+        "configuration change {id} reverted. Restart required: {}",
+        update.result.restart_required
+    );
+
+    if update.locale_changed {
+        apply_locale(&app, &state);
+    }
+
+    Ok(update.result)
+}
+
+/// List the names of the stored secrets.
+#[command]
+pub async fn list_secrets(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let vault = SecretVault::open(&state.runtime.paths.memospot_config_file)
+        .map_err(|e| format!("{e:#}"))?;
+    Ok(vault.names().map(String::from).collect())
+}
+
+/// Store a secret, to be referenced from environment variables as `secret:<name>`.
+#[command]
+pub async fn set_secret(
+    state: State<'_, AppState>,
+    name: String,
+    value: String,
+) -> Result<(), String> {
+    SecretVault::open(&state.runtime.paths.memospot_config_file)
+        .and_then(|mut vault| vault.set(&name, &value))
+        .map_err(|e| format!("{e:#}"))
+}
+
+/// Delete a stored secret. Returns whether it existed.
+#[command]
+pub async fn delete_secret(state: State<'_, AppState>, name: String) -> Result<bool, String> {
+    SecretVault::open(&state.runtime.paths.memospot_config_file)
+        .and_then(|mut vault| vault.remove(&name))
+        .map_err(|e| format!("{e:#}"))
+}
+
+/// Preview pending database migrations.
+///
+/// Migrations run against a temporary copy of the database and the
+/// resulting changes are reported. The live database is never modified.
+#[command]
+pub async fn dry_run_migrations(state: State<'_, AppState>) -> Result<DryRunReport, String> {
+    let db_file = &state.runtime.paths.memos_db_file;
+    if !state.runtime.active_server.managed || !db_file.exists() {
+        return Ok(DryRunReport::default());
+    }
+
+    migration::dry_run::<Migrator>(db_file)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Post a memo from the quick-capture window.
+#[command]
+pub async fn quick_capture(state: State<'_, AppState>, content: String) -> Result<(), String> {
+    let config = state.config.snapshot().current;
+    let server = &state.runtime.active_server;
+    capture::create_memo(
+        &server.url,
+        &server.user_agent,
+        &config.memospot.quick_capture,
+        &state.runtime.paths.memospot_config_file,
+        &content,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Get the recent resource usage samples of the managed Memos server, oldest first.
+#[command]
+pub async fn get_memos_resources(
+    monitor: State<'_, Monitor>,
+) -> Result<Vec<ResourceSample>, String> {
+    Ok(monitor.samples())
+}
+
 /// Check if a path exists.
 ///
 /// Tauri [implements](https://v2.tauri.app/plugin/file-system/#exists)
@@ -0,0 +1,249 @@
+//! Diagnostics bundle.
+//!
+//! Collects what's usually asked for in bug reports into a single zip file: the configuration,
+//! `log.yaml` and the logs, the Memospot, Memos, OS and webview versions, the runtime paths
+//! and the Memos environment.
+//!
+//! Values of keys matching `memospot.diagnostics.redact_keys` are redacted, along with all
+//! user-provided environment variables, unless `memospot.diagnostics.redact_env_values` is off.
+//! The same values are redacted from the logs.
+
+use crate::memos_version::MemosVersionStore;
+use crate::runtime_config::{AppState, RuntimeContext};
+use crate::{fl, memos, zip};
+use anyhow::Result;
+use config::{Config, SECRET_PREFIX, is_secret_key, redact_secrets, resolve_secrets};
+use dialog::*;
+use log::debug;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use sysinfo::System;
+use tauri::{AppHandle, Manager, Runtime, async_runtime};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Files copied from the data directory, if present.
+const DATA_FILES: [&str; 3] = ["log.yaml", "memospot.log", "memos.log"];
+
+/// Export a diagnostics bundle to the downloads directory, and show where it was saved.
+pub fn export_to_downloads<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let output_dir = app
+            .path()
+            .download_dir()
+            .unwrap_or_else(|_| state.runtime.paths.memospot_data.clone());
+        let config = state.config.snapshot().current;
+
+        match export(&state.runtime, &config, &output_dir).await {
+            Ok(file) => {
+                info_dialog!(fl!(
+                    "info-diagnostics-exported",
+                    file = file.to_string_lossy()
+                ));
+            }
+            Err(e) => {
+                warn_dialog!(fl!(
+                    "warn-failed-to-export-diagnostics",
+                    error = e.to_string()
+                ));
+            }
+        }
+    });
+}
+
+/// Write a diagnostics bundle to `output_dir` and return its path.
+pub async fn export(
+    runtime: &RuntimeContext,
+    config: &Config,
+    output_dir: &Path,
+) -> Result<PathBuf> {
+    let settings = &config.memospot.diagnostics;
+    let patterns = settings.redact_patterns();
+    let redact_env_values = settings.redact_env_values != Some(false);
+
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+    entries.push((
+        "memospot.yaml".into(),
+        redact_config(config, &patterns, redact_env_values)?
+            .to_string()?
+            .into_bytes(),
+    ));
+
+    let mut memos_version = MemosVersionStore::get();
+    if memos_version.is_empty() {
+        memos_version = memos::query_version(&runtime.active_server.url)
+            .await
+            .unwrap_or_default();
+    }
+
+    let user_env_keys: HashSet<&String> = match &runtime.memos.env.vars {
+        Some(vars) if runtime.memos.env.enabled == Some(true) => vars.keys().collect(),
+        _ => HashSet::new(),
+    };
+    let secret_keys = memos::secret_env_keys(&runtime.memos);
+    // Secret values, redacted wherever they appear in the logs.
+    let mut secret_values = config_secret_values(config, &patterns)?;
+    let env: BTreeMap<String, String> = memos::prepare_env(runtime)
+        .into_iter()
+        .map(|(key, value)| {
+            let is_secret = secret_keys.contains(&key) || is_secret_key(&key, &patterns);
+            if is_secret {
+                secret_values.push(value.clone());
+            }
+            let redact = is_secret || (redact_env_values && user_env_keys.contains(&key));
+            (key, if redact { REDACTED.into() } else { value })
+        })
+        .collect();
+    if let Some(vars) = &config.memospot.env.vars {
+        let (resolved, _) = resolve_secrets(vars, &runtime.paths.memospot_config_file);
+        secret_values.extend(resolved.into_iter().filter_map(|(key, value)| {
+            let is_secret =
+                vars[&key].starts_with(SECRET_PREFIX) || is_secret_key(&key, &patterns);
+            is_secret.then_some(value)
+        }));
+    }
+
+    // Keys are redacted even if the variables are disabled now, as they may be in older logs.
+    let mut log_keys = secret_keys.clone();
+    if redact_env_values {
+        for vars in [&config.memos.env.vars, &config.memospot.env.vars]
+            .into_iter()
+            .flatten()
+        {
+            log_keys.extend(vars.keys().cloned());
+        }
+    }
+    for name in DATA_FILES {
+        let path = runtime.paths.memospot_data.join(name);
+        match tokio::fs::read(&path).await {
+            Ok(contents) => {
+                let text = String::from_utf8_lossy(&contents);
+                let redacted = redact_log(&text, &log_keys, &patterns, &secret_values);
+                entries.push((name.into(), redacted.into_bytes()));
+            }
+            Err(e) => debug!("diagnostics: skipping {}: {e}", path.display()),
+        }
+    }
+
+    let system = json!({
+        "memospot": {
+            "version": env!("CARGO_PKG_VERSION"),
+            "git_hash": option_env!("GIT_SHORT_HASH"),
+        },
+        "memos": {
+            "version": memos_version,
+        },
+        "os": {
+            "name": System::long_os_version(),
+            "kernel": System::kernel_version(),
+            "arch": std::env::consts::ARCH,
+        },
+        "webview": tauri::webview_version().ok(),
+        "server": runtime.active_server,
+        "paths": runtime.paths,
+        "memos_env": env,
+    });
+    entries.push(("system.json".into(), serde_json::to_vec_pretty(&system)?));
+
+    let datetime = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let output = output_dir.join(format!("memospot-diagnostics-{datetime}.zip"));
+    zip::from_entries(&entries, &output).await?;
+    Ok(output)
+}
+
+/// Copy of `config` with secrets redacted.
+pub fn redact_config(
+    config: &Config,
+    patterns: &[String],
+    redact_env_values: bool,
+) -> Result<Config> {
+    let mut value = serde_json::to_value(config)?;
+    redact_secrets(&mut value, patterns, Some(REDACTED));
+    if redact_env_values {
+        for pointer in ["/memos/env/vars", "/memospot/env/vars"] {
+            if let Some(Value::Object(vars)) = value.pointer_mut(pointer) {
+                vars.values_mut()
+                    .for_each(|value| *value = Value::from(REDACTED));
+            }
+        }
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// String values of the configuration keys matching `patterns`.
+fn config_secret_values(config: &Config, patterns: &[String]) -> Result<Vec<String>> {
+    fn collect(value: &Value, patterns: &[String], values: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    match value {
+                        Value::String(s) if is_secret_key(key, patterns) => {
+                            values.push(s.clone())
+                        }
+                        value => collect(value, patterns, values),
+                    }
+                }
+            }
+            Value::Array(items) => items
+                .iter()
+                .for_each(|value| collect(value, patterns, values)),
+            _ => {}
+        }
+    }
+    let mut values = Vec::new();
+    collect(&serde_json::to_value(config)?, patterns, &mut values);
+    Ok(values)
+}
+
+/// Copy of log `text` with secrets redacted.
+///
+/// Redacts the values of `KEY=value` assignments and `"KEY": "value"` entries of logged
+/// maps whose key is in `keys` or matches `patterns`, then every occurrence of
+/// `secret_values`.
+pub fn redact_log(
+    text: &str,
+    keys: &HashSet<String>,
+    patterns: &[String],
+    secret_values: &[String],
+) -> String {
+    let redact_key = |key: &str| keys.contains(key) || is_secret_key(key, patterns);
+    let mut redacted = text
+        .split('\n')
+        .map(|line| redact_log_line(line, &redact_key))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Longest first, so a secret containing another one is redacted whole.
+    let mut secret_values: Vec<&String> = secret_values
+        .iter()
+        .filter(|value| !value.is_empty())
+        .collect();
+    secret_values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+    for value in secret_values {
+        redacted = redacted.replace(value.as_str(), REDACTED);
+    }
+    redacted
+}
+
+fn redact_log_line(line: &str, redact_key: &impl Fn(&str) -> bool) -> String {
+    let entry = line.trim_start();
+    if let Some((key, _)) = entry
+        .strip_prefix('"')
+        .and_then(|entry| entry.split_once("\": "))
+        && redact_key(key)
+    {
+        let indent = &line[..line.len() - entry.len()];
+        return format!("{indent}\"{key}\": \"{REDACTED}\",");
+    }
+
+    line.split(' ')
+        .map(|word| match word.split_once('=') {
+            Some((key, _)) if !key.is_empty() && redact_key(key) => format!("{key}={REDACTED}"),
+            _ => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -4,6 +4,7 @@ use std::sync::LazyLock;
 use std::sync::atomic::{AtomicU32, Ordering};
 
 use crate::cmd;
+use crate::diagnostics;
 use crate::memos;
 use crate::memos_version::MemosVersionStore;
 use crate::menu;
@@ -11,21 +12,23 @@ use crate::menu::MainMenu;
 use crate::menu::build_empty;
 use crate::route::Route;
 use crate::runtime_config::AppState;
+use crate::tray;
 use crate::updater;
 use crate::window::Window;
 use crate::window_ext::WebviewWindowExt;
 use anyhow::{Error, Result, bail};
 use dialog::error_dialog;
 use log::info;
-use log::{debug, error};
+use log::{debug, error, warn};
 #[cfg(not(debug_assertions))]
 use tauri::Url;
 use tauri::WebviewUrl;
 use tauri::WebviewWindow;
 use tauri::WebviewWindowBuilder;
 use tauri::WindowEvent;
-use tauri::{AppHandle, Manager, RunEvent, Runtime, async_runtime};
+use tauri::{AppHandle, Emitter, Manager, RunEvent, Runtime, async_runtime};
 use tauri_plugin_opener::OpenerExt;
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 
 pub static PREVENT_EXIT: LazyLock<std::sync::Mutex<bool>> =
@@ -38,6 +41,27 @@ pub(crate) const ZOOM_STEP: f64 = 0.1;
 const ZOOM_MIN: f64 = 0.2;
 const ZOOM_MAX: f64 = 5.0;
 pub(crate) const SHORTCUT_EVENT: &str = "memospot-shortcut";
+/// Emitted to all windows with a `ConfigChangedEvent` after the configuration changes.
+pub(crate) const CONFIG_CHANGED_EVENT: &str = "memospot-config-changed";
+
+/// Forward configuration changes from the store to all windows.
+pub(crate) fn forward_config_changes<R: Runtime>(app: &AppHandle<R>) {
+    let mut events = app.state::<AppState>().config.subscribe();
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    app.emit(CONFIG_CHANGED_EVENT, event).ok();
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("dropped {skipped} configuration change events");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
 
 /// Apply the current zoom level to all open webview windows.
 pub(crate) fn apply_zoom<R: Runtime>(app: &AppHandle<R>, zoom: f64) {
@@ -72,6 +96,8 @@ pub fn handle_run_events(app: &AppHandle, run_event: RunEvent) {
         RunEvent::MenuEvent { .. } => handle_menu_event(app, run_event)
             .unwrap_or_else(|e| error!("failed to handle menu event: {e}")),
         RunEvent::WindowEvent { .. } => handle_window_event(app, run_event),
+        #[cfg(target_os = "macos")]
+        RunEvent::Reopen { .. } => tray::show_main_window(app),
         _ => {}
     }
 }
@@ -220,6 +246,15 @@ fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, run_event: RunEvent) -> Res
         MainMenu::AppQuit => {
             app.exit(0);
         }
+        MainMenu::TrayShowWindow => {
+            tray::show_main_window(app);
+        }
+        MainMenu::TrayRestartServer => {
+            tray::restart_server(app);
+        }
+        MainMenu::TrayBackupNow => {
+            tray::backup_now(app);
+        }
 
         MainMenu::ViewNewWindow => {
             let main_title = main_window.title().unwrap_or_default();
@@ -302,6 +337,9 @@ fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, run_event: RunEvent) -> Res
         MainMenu::HelpMemospotReportIssue => {
             open_link("https://github.com/memospot/memospot/issues/new");
         }
+        MainMenu::HelpExportDiagnostics => {
+            diagnostics::export_to_downloads(app);
+        }
         MainMenu::HelpMemosDocumentation => {
             open_link("https://usememos.com/docs");
         }
@@ -340,7 +378,14 @@ where
                         w.persist_window_state(&app.state::<AppState>().config);
                     }
                 }
-                WindowEvent::CloseRequested { .. } => {
+                WindowEvent::CloseRequested { api, .. } => {
+                    if tray::should_close_to_tray(app) {
+                        api.prevent_close();
+                        if let Some(w) = app.get_webview_window(Window::Main.into()) {
+                            w.hide().ok();
+                        }
+                        return;
+                    }
                     // Close all windows except `main` itself.
                     app.webview_windows()
                         .into_iter()
@@ -0,0 +1,119 @@
+//! Headless mode.
+//!
+//! Runs Memospot as a Memos process manager, without a webview or window:
+//! orphan cleanup, port picking, migrations and backups happen as on GUI startup,
+//! then the server is supervised and restarted if it exits unexpectedly.
+//!
+//! SIGINT (Ctrl+C) and SIGTERM shut the server down cleanly and checkpoint the database.
+
+use crate::cli::Context;
+use crate::{init, memos};
+use anyhow::{Result, bail};
+use log::{info, warn};
+use std::time::Duration;
+use tauri::async_runtime;
+use tokio::time::Instant;
+
+/// Consecutive unexpected exits tolerated before giving up.
+const MAX_RESTARTS: u32 = 5;
+/// A server that stays up longer than this resets the restart counter.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+/// Delay before the first restart. Doubles on each consecutive restart.
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// Run in headless mode until a shutdown signal is received.
+///
+/// Refuses to start if the server of another instance is running, as cleaning up
+/// orphans would kill it.
+pub fn run(mut ctx: Context) -> Result<i32> {
+    if let Some(pid) = ctx.running_pid() {
+        bail!(
+            "Memos server is already running (PID {pid}). Close Memospot or run `memospot server stop` first"
+        );
+    }
+    init::setup_logger(&ctx.config, &ctx.runtime.paths.memospot_data);
+
+    memos::find_and_kill_orphaned(&ctx.runtime.memos, &ctx.runtime.paths.memospot_data);
+    ctx.prepare_server()?;
+
+    info!("Starting Memospot in headless mode.");
+    async_runtime::block_on(supervise(&ctx))
+}
+
+async fn supervise(ctx: &Context) -> Result<i32> {
+    let runtime = &ctx.runtime;
+    let url = &runtime.active_server.url;
+
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    let mut restarts: u32 = 0;
+    loop {
+        let exited = memos::spawn(runtime, &ctx.config)?;
+        let started = Instant::now();
+        {
+            let url = url.clone();
+            async_runtime::spawn(async move {
+                memos::wait_api_ready(&url).await;
+                println!("Memos server running at {url}");
+            });
+        }
+
+        tokio::select! {
+            // Prefer the signal, so a shutdown is never mistaken for a crash.
+            biased;
+            signal = &mut shutdown => {
+                let signal = signal?;
+                info!("headless: received {signal}. Shutting down…");
+                println!("Received {signal}. Shutting down…");
+                memos::shutdown(runtime).await;
+                return Ok(0);
+            }
+            status = exited => {
+                let code = status.ok().and_then(|payload| payload.code);
+                warn!("headless: server exited unexpectedly with code {code:?}");
+
+                if started.elapsed() > STABLE_UPTIME {
+                    restarts = 0;
+                }
+                restarts += 1;
+                if restarts > MAX_RESTARTS {
+                    memos::shutdown(runtime).await;
+                    bail!("Memos server exited {MAX_RESTARTS} times in a row. Giving up");
+                }
+
+                let delay = RESTART_DELAY * 2u32.pow(restarts - 1);
+                println!("Memos server exited with code {code:?}. Restarting in {delay:?}…");
+                tokio::select! {
+                    biased;
+                    signal = &mut shutdown => {
+                        let signal = signal?;
+                        info!("headless: received {signal} while waiting to restart");
+                        memos::shutdown(runtime).await;
+                        return Ok(0);
+                    }
+                    _ = tokio::time::sleep(delay) => {}
+                }
+            }
+        }
+    }
+}
+
+/// Wait for a shutdown signal and return its name.
+async fn shutdown_signal() -> Result<&'static str> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut sigterm = signal(SignalKind::terminate())?;
+        let name = tokio::select! {
+            result = tokio::signal::ctrl_c() => result.map(|_| "SIGINT")?,
+            _ = sigterm.recv() => "SIGTERM",
+        };
+        Ok(name)
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await?;
+        Ok("Ctrl+C")
+    }
+}
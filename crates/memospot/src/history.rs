@@ -0,0 +1,118 @@
+//! Configuration change history.
+//!
+//! Changes made through the configuration store are recorded as JSON Patches, along with
+//! their inverse, in `memospot.history.json` next to the configuration file. Any of them
+//! can be reverted, like an accidental change of the data directory.
+
+use json_patch::Patch;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Changes kept in the history. Older ones are dropped.
+const MAX_CHANGES: usize = 50;
+
+/// A recorded configuration change.
+#[derive(TS, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    /// Identifier, increasing with each change.
+    pub id: u64,
+    /// When the change was made, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The change, as a JSON Patch (RFC 6902).
+    #[ts(type = "Array<{ op: string, path: string, value?: unknown }>")]
+    pub patch: Patch,
+    /// JSON Patch reverting the change.
+    #[ts(type = "Array<{ op: string, path: string, value?: unknown }>")]
+    pub inverse: Patch,
+}
+
+/// Bounded history of configuration changes, saved to a file.
+#[derive(Debug)]
+pub struct ConfigHistory {
+    file: PathBuf,
+    changes: VecDeque<ConfigChange>,
+}
+
+impl ConfigHistory {
+    /// File name of the history, next to the configuration file.
+    pub const FILE_NAME: &str = "memospot.history.json";
+
+    /// Load the history of the configuration file `config_file`.
+    ///
+    /// Starts empty if there's no history, or it can't be read.
+    pub fn load(config_file: &Path) -> Self {
+        let file = config_file.with_file_name(Self::FILE_NAME);
+        let changes = match std::fs::read_to_string(&file) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("ignoring invalid configuration history: {e}");
+                VecDeque::new()
+            }),
+            Err(_) => VecDeque::new(),
+        };
+        Self { file, changes }
+    }
+
+    /// Recorded changes, oldest first.
+    pub fn changes(&self) -> impl Iterator<Item = &ConfigChange> {
+        self.changes.iter()
+    }
+
+    /// Recorded change `id`, if it's still in the history.
+    pub fn get(&self, id: u64) -> Option<&ConfigChange> {
+        self.changes.iter().find(|change| change.id == id)
+    }
+
+    /// Record the change from `before` to `after`, and save the history.
+    ///
+    /// Returns the recorded change, or `None` if nothing changed. Failing to save the history
+    /// is logged, as the change itself is already persisted.
+    pub async fn record(&mut self, before: &Value, after: &Value) -> Option<ConfigChange> {
+        let patch = json_patch::diff(before, after);
+        if patch.is_empty() {
+            return None;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let change = ConfigChange {
+            id: self.changes.back().map_or(1, |last| last.id + 1),
+            timestamp,
+            patch,
+            inverse: json_patch::diff(after, before),
+        };
+        self.changes.push_back(change.clone());
+        while self.changes.len() > MAX_CHANGES {
+            self.changes.pop_front();
+        }
+
+        if let Err(e) = self.save().await {
+            warn!("failed to save configuration history: {e}");
+        }
+        Some(change)
+    }
+
+    /// Save through an intermediate rename, so an interrupted write doesn't corrupt the
+    /// history.
+    async fn save(&self) -> std::io::Result<()> {
+        let json = serde_json::to_vec_pretty(&self.changes)?;
+        let tmp_file = self.file.with_file_name(format!("{}.tmp", Uuid::new_v4()));
+        let result = async {
+            let mut file = tokio::fs::File::create(&tmp_file).await?;
+            file.write_all(&json).await?;
+            file.sync_all().await?;
+            tokio::fs::rename(&tmp_file, &self.file).await
+        }
+        .await;
+        if result.is_err() {
+            tokio::fs::remove_file(&tmp_file).await.ok();
+        }
+        result
+    }
+}
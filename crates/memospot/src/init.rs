@@ -6,8 +6,7 @@ use crate::runtime_config::RuntimePaths;
 use crate::sqlite;
 use crate::utils::*;
 use crate::webview;
-use crate::zip;
-use config::{Config, Memos};
+use config::{Config, Memos, resolve_secrets};
 use dialog::*;
 use homedir::HomeDirExt;
 use log::{debug, info, warn};
@@ -22,6 +21,23 @@ use std::process::exit;
 use tokio::time::Instant;
 use writable::PathExt;
 
+/// Environment variable with Memospot's data directory, usable in configuration paths as
+/// `${MEMOSPOT_DATA}`.
+pub const DATA_ENV_VAR: &str = "MEMOSPOT_DATA";
+
+/// Expose Memospot's data directory as [`DATA_ENV_VAR`], unless it's already set.
+///
+/// Must be called before the configuration paths are expanded.
+pub fn set_data_env(memospot_data: &Path) {
+    if env::var_os(DATA_ENV_VAR).is_some() {
+        return;
+    }
+    // SAFETY: Called on startup, before other threads are spawned.
+    unsafe {
+        env::set_var(DATA_ENV_VAR, memospot_data);
+    }
+}
+
 /// Ensure that data directory exists and is writable.
 pub fn data_path(app_name: &str) -> PathBuf {
     let data_path = get_app_data_path(app_name);
@@ -46,25 +62,33 @@ pub fn data_path(app_name: &str) -> PathBuf {
 /// Use Memospot data directory if user-provided path is empty or ".".
 /// Optionally, resolve a user-provided data directory.
 pub fn memos_data(memos: &Memos, memospot_data: &Path) -> PathBuf {
+    resolve_memos_data(memos, memospot_data).unwrap_or_else(|path| {
+        panic_dialog!(fl!(
+            "panic-unable-to-resolve-custom-data-directory",
+            dir = path.to_string_lossy()
+        ));
+    })
+}
+
+/// Resolve Memos data directory without side effects.
+///
+/// Returns the unresolved path as an error if a user-provided directory does not exist.
+pub fn resolve_memos_data(memos: &Memos, memospot_data: &Path) -> Result<PathBuf, PathBuf> {
     let data_str = memos.data.as_ref().map(|s| s.as_str().trim()).unwrap_or("");
 
     // Use Memospot data directory if user-provided path is empty or ".".
     // Prevents resolving data path to a non-writable directory,
     // like /usr/local/bin or "Program Files".
     if data_str.is_empty() || data_str == "." {
-        return memospot_data.to_path_buf();
+        return Ok(memospot_data.to_path_buf());
     }
 
-    let expanded_path = PathBuf::from(data_str).expand_home().unwrap_or_default();
+    let expanded_path = PathBuf::from(data_str).expand().unwrap_or_default();
     let path = absolute_path(expanded_path).unwrap_or_else(|_| memospot_data.to_path_buf());
     if path.exists() && path.is_dir() {
-        return path;
+        return Ok(path);
     }
-
-    panic_dialog!(fl!(
-        "panic-unable-to-resolve-custom-data-directory",
-        dir = path.to_string_lossy()
-    ));
+    Err(path)
 }
 
 /// Ensure that backup directory exists and is writable.
@@ -72,26 +96,7 @@ pub fn memos_data(memos: &Memos, memospot_data: &Path) -> PathBuf {
 /// Use Memospot data directory if user-provided path is empty or ".".
 /// Optionally, resolve a user-provided directory.
 pub fn ensure_backup_directory(config: &Config, memospot_data: &Path) -> PathBuf {
-    let folder_name = "backups";
-    let default_path = memospot_data.join(folder_name);
-
-    let cfg_path = config
-        .memospot
-        .backups
-        .path
-        .as_ref()
-        .map(|s| s.as_str().trim())
-        .unwrap_or("");
-
-    // Use default directory if user-provided path is empty or ".".
-    // Prevents resolving data path to a non-writable directory,
-    // like /usr/local/bin or "Program Files".
-    let path: PathBuf = if cfg_path.is_empty() || cfg_path == "." || cfg_path == folder_name {
-        default_path
-    } else {
-        let expanded_path = PathBuf::from(cfg_path).expand_home().unwrap_or_default();
-        absolute_path(expanded_path).unwrap_or(default_path)
-    };
+    let path = backup_directory(config, memospot_data);
 
     if !path.exists() {
         std::fs::create_dir_all(&path).expect_dialog(fl!(
@@ -117,10 +122,33 @@ pub fn ensure_backup_directory(config: &Config, memospot_data: &Path) -> PathBuf
     path
 }
 
+/// Resolve backup directory without side effects.
+pub fn backup_directory(config: &Config, memospot_data: &Path) -> PathBuf {
+    let folder_name = "backups";
+    let default_path = memospot_data.join(folder_name);
+
+    let cfg_path = config
+        .memospot
+        .backups
+        .path
+        .as_ref()
+        .map(|s| s.as_str().trim())
+        .unwrap_or("");
+
+    // Use default directory if user-provided path is empty or ".".
+    // Prevents resolving data path to a non-writable directory,
+    // like /usr/local/bin or "Program Files".
+    if cfg_path.is_empty() || cfg_path == "." || cfg_path == folder_name {
+        default_path
+    } else {
+        let expanded_path = PathBuf::from(cfg_path).expand().unwrap_or_default();
+        absolute_path(expanded_path).unwrap_or(default_path)
+    }
+}
+
 /// Ensure that database files are writable, if they exist.
 pub fn database(memos: &Memos, memos_data: &Path) -> PathBuf {
-    let db_file = &format!("memos_{}.db", memos.mode.as_deref().unwrap_or_default());
-    let db_path = memos_data.join(db_file);
+    let db_path = database_path(memos, memos_data);
     let files = vec![
         db_path.with_extension("db"),
         db_path.with_extension("db-wal"),
@@ -154,6 +182,14 @@ pub fn database(memos: &Memos, memos_data: &Path) -> PathBuf {
     db_path
 }
 
+/// Resolve database file path for the configured Memos mode.
+pub fn database_path(memos: &Memos, memos_data: &Path) -> PathBuf {
+    memos_data.join(format!(
+        "memos_{}.db",
+        memos.mode.as_deref().unwrap_or_default()
+    ))
+}
+
 /// Run database migrations.
 pub async fn migrate_database(config: &Config, paths: &RuntimePaths) {
     if !config.memospot.migrations.enabled.unwrap_or_default() {
@@ -180,15 +216,11 @@ pub async fn migrate_database(config: &Config, paths: &RuntimePaths) {
     }
 
     if config.memospot.backups.enabled.unwrap_or_default() {
-        let backup_path = ensure_backup_directory(config, &paths.memospot_data);
-        let datetime = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
-        let backup_name = format!("db-{datetime}-pre-migration.zst.zip");
-        let backup_path = backup_path.join(&backup_name);
+        let backup_dir = ensure_backup_directory(config, &paths.memospot_data);
         let start_time = Instant::now();
-        let backup =
-            zip::related_files(&paths.memos_db_file, &["db-wal", "db-shm"], &backup_path);
+        let backup = sqlite::backup(&paths.memos_db_file, &backup_dir, "pre-migration");
         match backup.await {
-            Ok(_) => {
+            Ok(backup_path) => {
                 info!(
                     "database migration: backup completed. Operation took {:?}. Backup file: {}.",
                     start_time.elapsed(),
@@ -263,6 +295,8 @@ pub fn ensure_webview() {
 /// Initialize application configuration.
 ///
 /// - Ensure that configuration file exists and is writable.
+/// - Migrate configuration file from previous formats, keeping a backup.
+/// - Reset invalid values to their defaults, and warn about them.
 /// - If configuration file is missing or malformed, optionally reset it to defaults.
 pub fn config(config_path: &PathBuf) -> Config {
     if !config_path.exists() {
@@ -284,7 +318,25 @@ pub fn config(config_path: &PathBuf) -> Config {
         ));
     }
 
-    let mut cfg_reader = Config::init(config_path);
+    // Errors are reported by `init_validated` below.
+    if let Ok(Some(backup)) = Config::migrate_file_blocking(config_path) {
+        info!(
+            "configuration migrated. Previous file saved to {}",
+            backup.display()
+        );
+    }
+
+    let mut cfg_reader = Config::init_validated(config_path).map(|(cfg, errors)| {
+        if !errors.is_empty() {
+            let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            warn_dialog!(fl!(
+                "warn-config-invalid-values",
+                errors = errors.join("\n"),
+                file = config_path.to_string_lossy()
+            ));
+        }
+        cfg
+    });
     if let Err(e) = cfg_reader {
         let user_confirmed = confirm_dialog(
             fl!("prompt-config-error-title").as_str(),
@@ -336,6 +388,19 @@ pub fn memos_port(memos: &Memos) -> u16 {
 /// 5. /Applications/Memospot.app/Contents/MacOS/memos (macOS)
 /// 6. /usr/local/bin, /var/opt/memos, /usr/local/memos (Linux).
 pub fn find_memos(memos: &Memos, memospot_data: &Path, memospot_cwd: &Path) -> PathBuf {
+    locate_memos(memos, memospot_data, memospot_cwd).unwrap_or_else(|| {
+        panic_dialog!(fl!("panic-unable-to-find-memos-binary"));
+    })
+}
+
+/// Locate Memos server binary without side effects.
+///
+/// See [`find_memos`] for the search order.
+pub fn locate_memos(
+    memos: &Memos,
+    memospot_data: &Path,
+    memospot_cwd: &Path,
+) -> Option<PathBuf> {
     #[cfg(debug_assertions)]
     {
         // cwd is target/debug/ on dev.
@@ -350,7 +415,7 @@ pub fn find_memos(memos: &Memos, memospot_data: &Path, memospot_cwd: &Path) -> P
         let binary = server_dist_dir.join(format!("memos-{current_triple}"));
         if binary.exists() {
             warn!("Using Memos server from {binary:?}");
-            return binary;
+            return Some(binary);
         }
     }
 
@@ -358,10 +423,10 @@ pub fn find_memos(memos: &Memos, memospot_data: &Path, memospot_cwd: &Path) -> P
     if let Some(binary_path) = &memos.binary_path {
         let yaml_bin = binary_path.as_str().trim();
         if !yaml_bin.is_empty() {
-            let expanded_path = Path::new(yaml_bin).expand_home().unwrap_or_default();
+            let expanded_path = Path::new(yaml_bin).expand().unwrap_or_default();
             let path = absolute_path(expanded_path).unwrap_or_default();
             if path.exists() && path.is_file() {
-                return path;
+                return Some(path);
             }
         }
     }
@@ -404,11 +469,10 @@ pub fn find_memos(memos: &Memos, memospot_data: &Path, memospot_cwd: &Path) -> P
         let memos_path = path.join(binary_name);
         if memos_path.exists() && memos_path.is_file() {
             info!("Memos server found at: {}", memos_path.to_string_lossy());
-            return memos_path;
+            return Some(memos_path);
         }
     }
-
-    panic_dialog!(fl!("panic-unable-to-find-memos-binary"));
+    None
 }
 
 static LOG_CONFIG_YAML: &str = include_str!("log.default.yaml");
@@ -579,7 +643,8 @@ pub fn setup_hw_acceleration() {
 /// WEBKIT_DISABLE_COMPOSITING_MODE=1 to disable hardware acceleration on Linux.
 ///
 /// Should be called after init::hw_acceleration() to allow user-defined overrides.
-pub fn set_env_vars(config: &Config) {
+/// Secret references are resolved from the vault next to `config_file`.
+pub fn set_env_vars(config: &Config, config_file: &Path) {
     #[cfg(target_os = "linux")]
     setup_hw_acceleration();
 
@@ -588,7 +653,11 @@ pub fn set_env_vars(config: &Config) {
     }
 
     if let Some(memospot_env) = &config.memospot.env.vars {
-        for (key, value) in memospot_env {
+        let (resolved, errors) = resolve_secrets(memospot_env, config_file);
+        for error in errors {
+            warn!("{error:#}");
+        }
+        for (key, value) in &resolved {
             // SAFETY: There's potential for race conditions when setting environment
             // variables in a multithreaded context. Shouldn't be an issue here.
             unsafe {
@@ -598,7 +667,11 @@ pub fn set_env_vars(config: &Config) {
                     env::remove_var(key);
                     continue;
                 }
-                debug!("setting environment variable: {}={}", key, value);
+                // Log references to secrets, not their values.
+                debug!(
+                    "setting environment variable: {}={}",
+                    key, memospot_env[key]
+                );
                 env::set_var(key, value);
             }
         }
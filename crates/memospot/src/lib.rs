@@ -1,18 +1,26 @@
+mod capture;
+mod cli;
 mod cmd;
+mod diagnostics;
 mod event;
+mod headless;
+mod history;
 mod i18n;
 mod init;
 mod memos;
 mod memos_log;
 mod memos_version;
 mod menu;
+mod monitor;
 mod route;
 mod runtime_config;
 mod sqlite;
 #[cfg(test)]
 mod tests;
+mod tray;
 mod updater;
 mod utils;
+mod watcher;
 mod webview;
 mod window;
 mod window_ext;
@@ -25,17 +33,28 @@ use crate::runtime_config::apply_debug_overrides;
 use crate::runtime_config::{
     ActiveServer, AppState, ConfigStore, RuntimeContext, RuntimePaths,
 };
-use crate::window::Window;
+use config::SystemConfig;
 use dialog::*;
 use i18n::*;
 use log::{debug, info, warn};
 use std::env;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::webview::PageLoadEvent;
-use tauri::{Listener, Manager, async_runtime};
+use tauri::{Listener, async_runtime};
 use tauri_utils::config::WindowConfig;
 use window_ext::WindowConfigExt;
 
+/// Run a command-line maintenance task, if one was requested.
+///
+/// Returns the process exit code, or `None` if the GUI should start instead.
+pub fn run_cli() -> Option<i32> {
+    let args: Vec<String> = env::args_os()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    cli::run(&args)
+}
+
 #[warn(unused_extern_crates)]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -44,6 +63,7 @@ pub fn run() {
     init::ensure_webview();
 
     let memospot_data = init::data_path("memospot");
+    init::set_data_env(&memospot_data);
     let config_path = memospot_data.join("memospot.yaml");
     let mut current_config = init::config(&config_path);
     let initial_config = current_config.clone();
@@ -91,7 +111,7 @@ pub fn run() {
     let memospot_bin = env::current_exe().unwrap();
     let memospot_cwd = memospot_bin.parent().unwrap().to_path_buf();
 
-    init::set_env_vars(&current_config);
+    init::set_env_vars(&current_config, &config_path);
 
     {
         let url = memos_url.clone();
@@ -180,7 +200,8 @@ pub fn run() {
 
     let app_state = AppState {
         runtime,
-        config: ConfigStore::new(current_config, initial_config, config_path),
+        config: ConfigStore::new(current_config, initial_config, config_path)
+            .with_read_only(SystemConfig::locate().read_only_keys()),
     };
 
     if app_state.runtime.active_server.managed {
@@ -215,16 +236,18 @@ pub fn run() {
     let Ok(tauri_app) = tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_single_instance::init(|app, _, _| {
-            app.get_webview_window(Window::Main.into())
-                .map(|w| w.set_focus().ok());
+            // The main window may be hidden in the tray.
+            tray::show_main_window(app);
         }))
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(app_state)
+        .manage(monitor::Monitor::default())
         .invoke_handler(tauri::generate_handler![
             cmd::get_memos_url,
             cmd::get_theme,
@@ -235,8 +258,19 @@ pub fn run() {
             cmd::ping_memos,
             cmd::get_env,
             cmd::get_config,
+            cmd::get_config_provenance,
             cmd::get_default_config,
             cmd::set_config,
+            cmd::export_config,
+            cmd::import_config,
+            cmd::get_config_history,
+            cmd::revert_config_change,
+            cmd::list_secrets,
+            cmd::set_secret,
+            cmd::delete_secret,
+            cmd::dry_run_migrations,
+            cmd::quick_capture,
+            cmd::get_memos_resources,
             cmd::path_exists,
             cmd::zoom_in,
             cmd::zoom_out,
@@ -286,6 +320,11 @@ pub fn run() {
             // The menu must be set at the application level to also work in macOS.
             app.set_menu(menu::build(app_handle)?)?;
             menu::update_memos_version_entry(app_handle);
+            tray::setup(app_handle)?;
+            capture::setup(app_handle);
+            monitor::setup(app_handle);
+            watcher::setup(app_handle);
+            event::forward_config_changes(app_handle);
 
             if should_run_updater {
                 debug!("starting updater");
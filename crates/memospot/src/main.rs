@@ -2,5 +2,8 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if let Some(exit_code) = memospot_lib::run_cli() {
+        std::process::exit(exit_code);
+    }
     memospot_lib::run();
 }
@@ -4,22 +4,24 @@ use crate::sqlite;
 use crate::utils::absolute_path;
 use crate::{fl, memos_log};
 use anyhow::{Result, anyhow};
-use config::{Config, Memos};
+use config::{Config, Memos, SECRET_PREFIX, resolve_secrets};
 use dialog::error_dialog;
 use homedir::HomeDirExt;
 use log::{debug, error, info, warn};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 
+use sidecar::{CommandEvent, OutputMode, Receiver, TerminatedPayload, Termination};
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
-use sysinfo::{Pid, ProcessStatus, System};
+use sysinfo::{Pid, System};
 use tauri::async_runtime;
 use tauri::utils::platform::resource_dir as tauri_resource_dir;
 use tauri_plugin_http::reqwest;
 use tauri_utils::PackageInfo;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::oneshot;
 
 pub fn sync_mode_demo_compat(memos: &mut config::Memos) {
     let mode = match memos.mode.as_deref() {
@@ -201,44 +203,91 @@ async fn kill_pid(pid: u32) {
     }
 }
 
+/// Convert the configured resource limits for [`sidecar::Command::resource_limits`].
+fn resource_limits(limits: &config::ResourceLimits) -> sidecar::ResourceLimits {
+    if limits.enabled != Some(true) {
+        return sidecar::ResourceLimits::default();
+    }
+    sidecar::ResourceLimits {
+        address_space: limits
+            .max_memory_mb
+            .map(|mb| mb.saturating_mul(1024 * 1024)),
+        open_files: limits.max_open_files,
+        cpu_time: limits.max_cpu_seconds,
+        nice: limits.nice.map(|nice| nice.clamp(-20, 19)),
+        no_new_privileges: limits.no_new_privileges == Some(true),
+    }
+}
+
 /// Spawn Memos server.
 ///
 /// Spawns a managed child process with custom environment variables.
 ///
 /// `runtime` carries the startup snapshot of the server settings and paths;
 /// `config` provides the Memospot-side settings read at spawn time.
-pub fn spawn(runtime: &RuntimeContext, config: &Config) -> Result<(), anyhow::Error> {
+///
+/// Returns a receiver that resolves once the server process terminates.
+pub fn spawn(
+    runtime: &RuntimeContext,
+    config: &Config,
+) -> Result<oneshot::Receiver<TerminatedPayload>, anyhow::Error> {
     let env_vars: HashMap<String, String> = prepare_env(runtime);
     let command = runtime.paths.memos_bin.to_string_lossy().to_string();
     let cwd = get_cwd(runtime);
 
     debug!("working directory: {}", cwd.to_string_lossy());
-    debug!("environment: {env_vars:#?}");
+    let secret_keys = secret_env_keys(&runtime.memos);
+    let logged_env: BTreeMap<&String, &str> = env_vars
+        .iter()
+        .map(|(key, value)| {
+            let value = if secret_keys.contains(key) {
+                "<secret>"
+            } else {
+                value
+            };
+            (key, value)
+        })
+        .collect();
+    debug!("environment: {logged_env:#?}");
 
     const MAX_RETRIES: usize = 2;
+    const MAX_LOG_LINE_LENGTH: usize = 64 * 1024;
     let mut last_error = anyhow!("failed to spawn Memos server");
 
     for _ in 1..=MAX_RETRIES {
         let res = sidecar::Command::new(&command)
             .envs(env_vars.clone())
             .current_dir(cwd.clone())
+            // Keep the server out of the terminal's process group, and never leave it
+            // running if Memospot crashes.
+            .process_group(true)
+            .kill_on_parent_death(true)
+            // Don't drop log lines over a stray invalid byte, nor buffer runaway lines.
+            .output_mode(OutputMode::LossyLines)
+            .max_line_length(MAX_LOG_LINE_LENGTH)
+            .resource_limits(resource_limits(&runtime.memos.limits))
             .spawn();
 
         match res {
-            Ok(receiver, ..) => {
-                if config.memospot.log.enabled.unwrap_or(false) {
-                    let (rx_, _) = receiver;
-                    async_runtime::spawn(async move {
-                        memos_log::log_events(rx_).await;
-                    });
-                }
+            Ok((events, child)) => {
+                let log_enabled = config.memospot.log.enabled.unwrap_or(false);
+                let (exit_tx, exit_rx) = oneshot::channel();
+                async_runtime::spawn(async move {
+                    let terminated = if log_enabled {
+                        memos_log::log_events(events).await
+                    } else {
+                        wait_terminated(events).await
+                    };
+                    if let Some(payload) = terminated {
+                        exit_tx.send(payload).ok();
+                    }
+                });
 
-                let (_, child) = receiver;
                 let pid_file = runtime.paths.memospot_data.join("memos.pid");
 
                 save_pid_file(child.pid(), &pid_file);
 
-                return Ok(());
+                return Ok(exit_rx);
             }
             Err(e) => {
                 last_error = last_error.context(e);
@@ -269,6 +318,19 @@ pub fn spawn(runtime: &RuntimeContext, config: &Config) -> Result<(), anyhow::Er
     Err(last_error)
 }
 
+/// Drain server events without logging them.
+///
+/// Returns the termination payload, if the server exited.
+async fn wait_terminated(mut events: Receiver<CommandEvent>) -> Option<TerminatedPayload> {
+    let mut terminated = None;
+    while let Some(event) = events.recv().await {
+        if let CommandEvent::Terminated(payload) = event {
+            terminated = Some(payload);
+        }
+    }
+    terminated
+}
+
 /// Shutdown the Memos server and checkpoint the database.
 pub async fn shutdown(runtime: &RuntimeContext) {
     if !runtime.active_server.managed {
@@ -277,40 +339,26 @@ pub async fn shutdown(runtime: &RuntimeContext) {
     }
 
     debug!("shutting down server…");
-    sidecar::kill_children();
-
-    if let Some(pid) = get_last_pid(&runtime.paths.memospot_data) {
-        let pid = Pid::from_u32(pid);
-
-        let time_start = tokio::time::Instant::now();
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(50));
-        loop {
-            interval.tick().await;
-            if time_start.elapsed() > tokio::time::Duration::from_secs(5) {
-                warn!("timed out after 5 seconds waiting for pid {pid} to exit");
-                break;
-            }
-
-            let sys = System::new_all();
-            if let Some(proc) = sys.process(pid) {
-                match proc.status() {
-                    ProcessStatus::Run => {
-                        proc.kill_and_wait().ok();
-                    }
-                    ProcessStatus::Sleep => {
-                        proc.kill_and_wait().ok();
-                    }
-                    _ => {
-                        break;
-                    }
-                }
-            } else {
-                debug!("pid {pid} is not running anymore");
-                break;
+    let terminations = sidecar::terminate_children().await;
+    for (pid, termination) in &terminations {
+        match termination {
+            Termination::TimedOut { elapsed } => {
+                warn!("timed out after {elapsed:?} waiting for pid {pid} to exit")
             }
+            _ if !termination.is_graceful() => warn!("pid {pid} was killed: {termination:?}"),
+            _ => debug!("pid {pid} exited: {termination:?}"),
         }
     }
 
+    // The server may not be a child of this process, like when started from the command line.
+    if let Some(pid) = get_last_pid(&runtime.paths.memospot_data)
+        && !terminations.iter().any(|(child, _)| *child == pid)
+        && System::new_all().process(Pid::from_u32(pid)).is_some()
+    {
+        debug!("pid {pid} is not a child process. Killing it…");
+        kill_pid(pid).await;
+    }
+
     let db_file = runtime.paths.memos_db_file.as_ref();
     sqlite::wait_checkpoint(db_file).await;
     remove_pid_file(&runtime.paths.memospot_data).await;
@@ -339,7 +387,7 @@ pub fn get_cwd(runtime: &RuntimeContext) -> PathBuf {
         && !working_dir.trim().is_empty()
     {
         Path::new(working_dir)
-            .expand_home()
+            .expand()
             .map(|expanded| absolute_path(expanded).map(|absolute| search_paths.push(absolute)))
             .ok();
     }
@@ -432,13 +480,16 @@ pub fn prepare_env(runtime: &RuntimeContext) -> HashMap<String, String> {
 
     let mut env_vars: HashMap<String, String> = HashMap::new();
 
-    // Add user-provided environment variables.
+    // Add user-provided environment variables, with secrets resolved from the vault.
     if memos.env.enabled.unwrap_or_default()
         && let Some(memos_env) = &memos.env.vars
     {
-        for (key, value) in memos_env {
-            env_vars.insert(key.into(), value.into());
+        let (memos_env, errors) =
+            resolve_secrets(memos_env, &runtime.paths.memospot_config_file);
+        for error in errors {
+            warn!("{error:#}");
         }
+        env_vars.extend(memos_env);
     }
 
     // Add managed environment variables. The default insert()
@@ -449,6 +500,20 @@ pub fn prepare_env(runtime: &RuntimeContext) -> HashMap<String, String> {
     env_vars
 }
 
+/// User-provided environment variables whose values come from the secrets vault.
+///
+/// Empty if the variables are disabled, as they aren't passed to the server then.
+pub fn secret_env_keys(memos: &Memos) -> HashSet<String> {
+    match &memos.env.vars {
+        Some(vars) if memos.env.enabled == Some(true) => vars
+            .iter()
+            .filter(|(_, value)| value.starts_with(SECRET_PREFIX))
+            .map(|(key, _)| key.clone())
+            .collect(),
+        _ => HashSet::new(),
+    }
+}
+
 /// Query Memos version via API.
 ///
 /// Supports:
@@ -3,9 +3,13 @@
 //! This is split as a module so `log4rs` can filter out the logs.
 
 use log::{error, info, warn};
-use sidecar::{CommandEvent, Receiver};
+use sidecar::{CommandEvent, Receiver, TerminatedPayload};
 
-pub async fn log_events(mut events: Receiver<CommandEvent>) {
+/// Log server events until the event stream ends.
+///
+/// Returns the termination payload, if the server exited.
+pub async fn log_events(mut events: Receiver<CommandEvent>) -> Option<TerminatedPayload> {
+    let mut terminated = None;
     while let Some(event) = events.recv().await {
         match event {
             CommandEvent::Error(e) => {
@@ -22,8 +26,10 @@ pub async fn log_events(mut events: Receiver<CommandEvent>) {
                     "-- server exited with code {:?} --\n",
                     term.code.unwrap_or_default()
                 );
+                terminated = Some(term);
             }
             _ => {}
         }
     }
+    terminated
 }
@@ -69,6 +69,8 @@ pub enum MainMenu {
     HelpMemospotReleaseNotes,
     #[strum(serialize = "helpmenu-report-issue")]
     HelpMemospotReportIssue,
+    #[strum(serialize = "helpmenu-export-diagnostics")]
+    HelpExportDiagnostics,
     #[strum(serialize = "helpmenu-memospot-version")]
     HelpMemospotVersion,
     #[strum(serialize = "helpmenu-documentation")]
@@ -77,6 +79,12 @@ pub enum MainMenu {
     HelpMemosReleaseNotes,
     #[strum(serialize = "helpmenu-memos-version")]
     HelpMemosVersion,
+    #[strum(serialize = "traymenu-show-window")]
+    TrayShowWindow,
+    #[strum(serialize = "traymenu-restart-server")]
+    TrayRestartServer,
+    #[strum(serialize = "traymenu-backup-now")]
+    TrayBackupNow,
 }
 impl MainMenu {
     /// Get the MenuId for the item.
@@ -256,6 +264,11 @@ pub fn build<R: Runtime>(handle: &AppHandle<R>) -> tauri::Result<Menu<R>> {
                 MainMenu::HelpMemospotReportIssue.text(),
             )
             .build(handle)?,
+            &MenuItemBuilder::with_id(
+                MainMenu::HelpExportDiagnostics.id(),
+                MainMenu::HelpExportDiagnostics.text(),
+            )
+            .build(handle)?,
             &PredefinedMenuItem::separator(handle)?,
             &MenuItemBuilder::with_id(
                 MainMenu::HelpMemosVersion.id(),
@@ -0,0 +1,177 @@
+//! Memos server resource monitor.
+//!
+//! Samples the managed server CPU, memory and open files, along with the database size,
+//! on an interval. The latest samples are kept for the diagnostics panel
+//! ([`crate::cmd::get_memos_resources`]), and a warning is logged whenever a configured
+//! threshold is crossed.
+
+use crate::memos;
+use crate::runtime_config::AppState;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager, Runtime, async_runtime};
+use ts_rs::TS;
+
+/// Number of samples kept.
+const HISTORY_SIZE: usize = 360;
+const DEFAULT_INTERVAL_SECS: u64 = 10;
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// Resource usage of the Memos server at a point in time.
+#[derive(TS, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ResourceSample {
+    /// Sample time, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Server process id.
+    pub pid: u32,
+    /// CPU usage since the previous sample, in percent. 100% is one full core.
+    pub cpu_percent: f32,
+    /// Resident memory, in bytes.
+    pub memory: u64,
+    /// Open file descriptors, if available on this platform.
+    pub open_files: Option<u64>,
+    /// Database file size, in bytes.
+    pub db_size: u64,
+    /// Database WAL file size, in bytes.
+    pub wal_size: u64,
+    /// Server uptime, in seconds.
+    pub uptime: u64,
+}
+
+/// Recent resource samples. Managed by Tauri.
+#[derive(Debug, Default)]
+pub struct Monitor {
+    samples: Mutex<VecDeque<ResourceSample>>,
+}
+
+impl Monitor {
+    /// Recent samples, oldest first.
+    pub fn samples(&self) -> Vec<ResourceSample> {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.iter().cloned().collect()
+    }
+
+    fn push(&self, sample: ResourceSample) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        if samples.len() == HISTORY_SIZE {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+}
+
+/// Start sampling the managed Memos server.
+///
+/// The settings are read on each tick, so they apply without a restart.
+/// Requires [`Monitor`] to be managed.
+pub fn setup<R: Runtime>(app: &AppHandle<R>) {
+    if !app.state::<AppState>().runtime.active_server.managed {
+        debug!("resource monitor is disabled: the server is not managed");
+        return;
+    }
+
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        let mut system = System::new();
+        let mut exceeded = HashSet::new();
+        loop {
+            let state = app.state::<AppState>();
+            let settings = state.config.snapshot().current.memospot.monitor.clone();
+            let interval = settings.interval.unwrap_or(DEFAULT_INTERVAL_SECS).max(1);
+            if settings.enabled == Some(true) {
+                let paths = &state.runtime.paths;
+                if let Some(sample) = memos::get_last_pid(&paths.memospot_data)
+                    .and_then(|pid| sample(&mut system, pid, &paths.memos_db_file))
+                {
+                    check_thresholds(&settings, &sample, &mut exceeded);
+                    app.state::<Monitor>().push(sample);
+                }
+            } else {
+                exceeded.clear();
+            }
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    });
+}
+
+/// Sample the resource usage of `pid`. Returns `None` if the process is gone.
+fn sample(system: &mut System, pid: u32, db_file: &Path) -> Option<ResourceSample> {
+    let pid = Pid::from_u32(pid);
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing().with_cpu().with_memory(),
+    );
+    let process = system.process(pid)?;
+
+    let file_size = |path: &Path| path.metadata().map(|m| m.len()).unwrap_or_default();
+    let mut wal_file = db_file.as_os_str().to_owned();
+    wal_file.push("-wal");
+
+    Some(ResourceSample {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default(),
+        pid: pid.as_u32(),
+        cpu_percent: process.cpu_usage(),
+        memory: process.memory(),
+        open_files: process.open_files().map(|n| n as u64),
+        db_size: file_size(db_file),
+        wal_size: file_size(Path::new(&wal_file)),
+        uptime: process.run_time(),
+    })
+}
+
+/// Log when a metric crosses its threshold, either way.
+///
+/// `exceeded` tracks the metrics above their threshold, so each crossing is logged once.
+fn check_thresholds(
+    settings: &config::Monitor,
+    sample: &ResourceSample,
+    exceeded: &mut HashSet<&'static str>,
+) {
+    let checks = [
+        (
+            "CPU usage",
+            f64::from(sample.cpu_percent),
+            settings.warn_cpu_percent.map(f64::from),
+            "%",
+        ),
+        (
+            "memory",
+            sample.memory as f64 / MIB,
+            settings.warn_memory_mb.map(|mb| mb as f64),
+            " MiB",
+        ),
+        (
+            "open files",
+            sample.open_files.unwrap_or_default() as f64,
+            settings.warn_open_files.map(|n| n as f64),
+            "",
+        ),
+        (
+            "WAL size",
+            sample.wal_size as f64 / MIB,
+            settings.warn_wal_mb.map(|mb| mb as f64),
+            " MiB",
+        ),
+    ];
+    for (metric, value, threshold, unit) in checks {
+        let Some(threshold) = threshold else {
+            continue;
+        };
+        if value > threshold {
+            if exceeded.insert(metric) {
+                warn!("Memos {metric} is {value:.1}{unit}, above {threshold}{unit}");
+            }
+        } else if exceeded.remove(metric) {
+            info!("Memos {metric} is back to {value:.1}{unit}");
+        }
+    }
+}
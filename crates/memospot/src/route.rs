@@ -11,6 +11,8 @@ pub enum Route {
     Loader,
     #[strum(serialize = "/settings")]
     Settings,
+    #[strum(serialize = "/capture")]
+    Capture,
 }
 impl Route {
     pub fn id(&self) -> u8 {
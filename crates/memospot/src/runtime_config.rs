@@ -3,12 +3,15 @@
 //! [`AppState`] is the single Tauri-managed source of truth for the editable
 //! configuration and the immutable runtime context derived from it at startup.
 
+use crate::history::{ConfigChange, ConfigHistory};
 use config::Config;
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::broadcast;
 use ts_rs::TS;
 
 /// Runtime paths used throughout the app.
@@ -95,6 +98,70 @@ pub struct ConfigUpdate {
     pub locale_changed: bool,
 }
 
+/// Result of merging external changes to the configuration file.
+#[derive(TS, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ConfigReloadResult {
+    /// Whether some changes only take effect after restarting Memospot.
+    pub restart_required: bool,
+    /// Dotted keys whose unsaved in-app changes were replaced by the file's values.
+    pub conflicts: Vec<String>,
+}
+
+/// Internal result of merging external changes to the configuration file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConfigReload {
+    /// Result exposed to the frontend.
+    pub result: ConfigReloadResult,
+    /// Whether the file changed the locale preference.
+    pub locale_changed: bool,
+}
+
+/// Result of importing a configuration document.
+#[derive(TS, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ConfigImportResult {
+    /// Changes to the current configuration, as a JSON Patch (RFC 6902).
+    #[ts(type = "Array<{ op: string, path: string, value?: unknown }>")]
+    pub patch: Patch,
+    /// Whether the changes were saved. Dry runs only report them.
+    pub applied: bool,
+    /// Whether the changes only take effect after restarting Memospot.
+    pub restart_required: bool,
+}
+
+/// Internal result of importing a configuration document.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConfigImport {
+    /// Result exposed to the frontend.
+    pub result: ConfigImportResult,
+    /// Whether the import changed the persisted locale preference.
+    pub locale_changed: bool,
+}
+
+/// A changed configuration value.
+#[derive(TS, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ConfigValueChange {
+    /// Dotted key, like `memos.port`.
+    pub key: String,
+    /// JSON Pointer to the value, like `/memos/port`.
+    pub path: String,
+    /// Previous value, `null` if it was unset.
+    #[ts(type = "unknown")]
+    pub old: Value,
+    /// New value, `null` if it's now unset.
+    #[ts(type = "unknown")]
+    pub new: Value,
+}
+
+/// Broadcast after the current configuration changes, from any window or the file.
+#[derive(TS, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ConfigChangedEvent {
+    /// Changed values, in key order.
+    pub changes: Vec<ConfigValueChange>,
+    /// Whether the configuration now differs from the running one in settings that only
+    /// take effect after restarting Memospot.
+    pub restart_required: bool,
+}
+
 /// Errors that can occur while updating the managed configuration.
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -107,6 +174,12 @@ pub enum ConfigError {
     /// The configuration could not be persisted to disk.
     #[error("failed to persist configuration: {0}")]
     Persistence(String),
+    /// The change targets a key set by the system policy.
+    #[error("`{0}` is set by the system policy and can't be changed")]
+    ReadOnly(String),
+    /// The change to revert is not in the history.
+    #[error("configuration change {0} not found in the history")]
+    UnknownChange(u64),
 }
 
 /// Synchronized configuration store.
@@ -119,9 +192,15 @@ pub enum ConfigError {
 pub struct ConfigStore {
     current: Arc<RwLock<Arc<Config>>>,
     initial: Arc<Config>,
+    /// The configuration as last read from or written to the file.
+    persisted: Arc<RwLock<Arc<Config>>>,
     restart_baseline: Arc<RwLock<Arc<Config>>>,
     writer: Arc<tokio::sync::Mutex<()>>,
     config_file: PathBuf,
+    /// Dotted keys set by the system policy.
+    read_only: Arc<Vec<String>>,
+    history: Arc<tokio::sync::Mutex<ConfigHistory>>,
+    events: broadcast::Sender<ConfigChangedEvent>,
     pending_window_state: Arc<Mutex<Option<WindowState>>>,
     window_update_scheduled: Arc<AtomicBool>,
     window_update_notify: Arc<tokio::sync::Notify>,
@@ -143,15 +222,37 @@ impl ConfigStore {
         Self {
             restart_baseline: Arc::new(RwLock::new(Arc::new(current.clone()))),
             current: Arc::new(RwLock::new(Arc::new(current))),
+            persisted: Arc::new(RwLock::new(Arc::new(initial.clone()))),
             initial: Arc::new(initial),
             writer: Arc::new(tokio::sync::Mutex::new(())),
+            history: Arc::new(tokio::sync::Mutex::new(ConfigHistory::load(&config_file))),
             config_file,
+            read_only: Arc::default(),
+            events: broadcast::channel(16).0,
             pending_window_state: Arc::new(Mutex::new(None)),
             window_update_scheduled: Arc::new(AtomicBool::new(false)),
             window_update_notify: Arc::new(tokio::sync::Notify::new()),
         }
     }
 
+    /// Reject changes to `keys`, dotted keys set by the system policy.
+    pub fn with_read_only(mut self, keys: Vec<String>) -> Self {
+        self.read_only = Arc::new(keys);
+        self
+    }
+
+    /// Dotted keys set by the system policy.
+    pub fn read_only_keys(&self) -> &[String] {
+        &self.read_only
+    }
+
+    /// Receive a [`ConfigChangedEvent`] after each change to the current configuration.
+    ///
+    /// Runtime-owned updates, like the window geometry, are not broadcast.
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigChangedEvent> {
+        self.events.subscribe()
+    }
+
     /// Consistent read of the store: the current configuration and
     /// the startup baseline.
     pub fn snapshot(&self) -> ConfigSnapshot {
@@ -183,12 +284,7 @@ impl ConfigStore {
         let restart_required = restart_required(&restart_baseline, &candidate);
         let locale_changed = current.memospot.window.locale != candidate.memospot.window.locale;
 
-        candidate
-            .save_to_file(&self.config_file)
-            .await
-            .map_err(|e| ConfigError::Persistence(e.to_string()))?;
-
-        *self.current.write().expect("config lock poisoned") = Arc::new(candidate);
+        self.commit(&current, candidate).await?;
         Ok(ConfigUpdate {
             result: ConfigUpdateResult { restart_required },
             locale_changed,
@@ -219,18 +315,78 @@ impl ConfigStore {
         let restart_required = restart_required(&restart_baseline, &candidate);
         let locale_changed = current.memospot.window.locale != candidate.memospot.window.locale;
 
-        candidate
-            .save_to_file(&self.config_file)
-            .await
-            .map_err(|e| ConfigError::Persistence(e.to_string()))?;
-
-        *self.current.write().expect("config lock poisoned") = Arc::new(candidate);
+        self.commit(&current, candidate).await?;
         Ok(ConfigUpdate {
             result: ConfigUpdateResult { restart_required },
             locale_changed,
         })
     }
 
+    /// Import a configuration document, as exported by [`Config::export`].
+    ///
+    /// The changes are applied as a JSON Patch, like settings updates. Keys set by the
+    /// system policy are left out. With `dry_run`, the changes are only reported.
+    pub async fn import(
+        &self,
+        document: &str,
+        dry_run: bool,
+    ) -> Result<ConfigImport, ConfigError> {
+        let current = self.snapshot().current;
+        let imported = current
+            .import(document)
+            .map_err(|e| ConfigError::InvalidConfig(format!("{e:#}")))?;
+        let to_value = |config: &Config| {
+            serde_json::to_value(config).map_err(|e| ConfigError::InvalidConfig(e.to_string()))
+        };
+        let mut patch = json_patch::diff(&to_value(&current)?, &to_value(&imported)?);
+        patch
+            .0
+            .retain(|op| !self.is_read_only_pointer(op.path().as_str()));
+
+        if dry_run || patch.is_empty() {
+            let restart_baseline = self
+                .restart_baseline
+                .read()
+                .expect("config lock poisoned")
+                .clone();
+            let candidate = normalize_config(apply_patch(&current, &patch)?);
+            return Ok(ConfigImport {
+                result: ConfigImportResult {
+                    restart_required: restart_required(&restart_baseline, &candidate),
+                    patch,
+                    applied: false,
+                },
+                locale_changed: false,
+            });
+        }
+
+        let update = self.apply_patch_and_persist(&patch).await?;
+        Ok(ConfigImport {
+            result: ConfigImportResult {
+                patch,
+                applied: true,
+                restart_required: update.result.restart_required,
+            },
+            locale_changed: update.locale_changed,
+        })
+    }
+
+    /// Recorded configuration changes, oldest first.
+    pub async fn history(&self) -> Vec<ConfigChange> {
+        self.history.lock().await.changes().cloned().collect()
+    }
+
+    /// Revert a recorded change by applying its inverse patch.
+    ///
+    /// The revert is recorded as a change too, so it can be reverted as well.
+    pub async fn revert(&self, id: u64) -> Result<ConfigUpdate, ConfigError> {
+        let inverse = match self.history.lock().await.get(id) {
+            Some(change) => change.inverse.clone(),
+            None => return Err(ConfigError::UnknownChange(id)),
+        };
+        self.apply_patch_and_persist(&inverse).await
+    }
+
     /// Merge runtime-owned fields into the current configuration.
     ///
     /// Used for in-session fields such as window state. The update is not
@@ -317,23 +473,154 @@ impl ConfigStore {
         }
     }
 
-    /// Persist the current configuration if it differs from the startup baseline.
+    /// Persist the current configuration if it differs from the file.
     ///
-    /// Called at shutdown. The baseline comparison is explicit and never
-    /// mutates the current configuration.
+    /// Called at shutdown. Only changed keys are written, so external edits merged by
+    /// [`reload`](Self::reload) are kept, and the current configuration is never mutated.
     pub async fn finalize_persistence(&self) -> Result<(), ConfigError> {
         self.flush_runtime_owned_updates().await;
         let _writer = self.writer.lock().await;
-        let snapshot = self.snapshot();
-        if snapshot.current != snapshot.initial {
-            snapshot
-                .current
-                .save_to_file(&self.config_file)
-                .await
-                .map_err(|e| ConfigError::Persistence(e.to_string()))?;
+        let current = self.snapshot().current;
+        if *current != **self.persisted.read().expect("config lock poisoned") {
+            self.persist((*current).clone()).await?;
         }
         Ok(())
     }
+
+    /// Merge external changes to the configuration file.
+    ///
+    /// `external` is the configuration as read back from the file. Values changed in the
+    /// file replace the current ones, including unsaved in-app changes, which are reported
+    /// as conflicts. Returns `None` if the file is unchanged since it was last read or
+    /// written by the store, like after the store's own writes.
+    pub async fn reload(&self, external: Config) -> Result<Option<ConfigReload>, ConfigError> {
+        let _writer = self.writer.lock().await;
+
+        let persisted = self.persisted.read().expect("config lock poisoned").clone();
+        if *persisted == external {
+            return Ok(None);
+        }
+
+        let current = self.snapshot().current;
+        let to_value =
+            |config: &Config| serde_json::to_value(config).map_err(|e| e.to_string());
+        let mut conflicts = Vec::new();
+        let merged = merge_external(
+            &to_value(&persisted).map_err(ConfigError::InvalidConfig)?,
+            &to_value(&current).map_err(ConfigError::InvalidConfig)?,
+            &to_value(&external).map_err(ConfigError::InvalidConfig)?,
+            &mut Vec::new(),
+            &mut conflicts,
+        );
+        let candidate = normalize_config(
+            serde_json::from_value(merged)
+                .map_err(|e| ConfigError::InvalidConfig(e.to_string()))?,
+        );
+
+        let restart_baseline = self
+            .restart_baseline
+            .read()
+            .expect("config lock poisoned")
+            .clone();
+        let restart_required = restart_required(&restart_baseline, &candidate);
+        let locale_changed = current.memospot.window.locale != candidate.memospot.window.locale;
+
+        let (before, after) = (
+            to_value(&current).map_err(ConfigError::InvalidConfig)?,
+            to_value(&candidate).map_err(ConfigError::InvalidConfig)?,
+        );
+        *self.persisted.write().expect("config lock poisoned") = Arc::new(external);
+        *self.current.write().expect("config lock poisoned") = Arc::new(candidate);
+        self.broadcast(&before, &after, restart_required);
+        Ok(Some(ConfigReload {
+            result: ConfigReloadResult {
+                restart_required,
+                conflicts,
+            },
+            locale_changed,
+        }))
+    }
+
+    /// Fail if `candidate` changes a key set by the system policy.
+    fn check_read_only(&self, current: &Config, candidate: &Config) -> Result<(), ConfigError> {
+        if self.read_only.is_empty() {
+            return Ok(());
+        }
+        let current = serde_json::to_value(current)
+            .map_err(|e| ConfigError::InvalidConfig(e.to_string()))?;
+        let candidate = serde_json::to_value(candidate)
+            .map_err(|e| ConfigError::InvalidConfig(e.to_string()))?;
+        for key in self.read_only.iter() {
+            let pointer = json_pointer(key);
+            if current.pointer(&pointer) != candidate.pointer(&pointer) {
+                return Err(ConfigError::ReadOnly(key.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the JSON Pointer `pointer` targets, or contains, a key set by the system policy.
+    fn is_read_only_pointer(&self, pointer: &str) -> bool {
+        self.read_only.iter().any(|key| {
+            let read_only = json_pointer(key);
+            pointer == read_only
+                || pointer.starts_with(&format!("{read_only}/"))
+                || read_only.starts_with(&format!("{pointer}/"))
+        })
+    }
+
+    /// Persist `candidate`, record the change from `current` in the history, and broadcast it.
+    ///
+    /// Must be called with the writer lock held.
+    async fn commit(&self, current: &Config, candidate: Config) -> Result<(), ConfigError> {
+        self.check_read_only(current, &candidate)?;
+        let to_value = |config: &Config| {
+            serde_json::to_value(config).map_err(|e| ConfigError::InvalidConfig(e.to_string()))
+        };
+        let (before, after) = (to_value(current)?, to_value(&candidate)?);
+        let restart_baseline = self
+            .restart_baseline
+            .read()
+            .expect("config lock poisoned")
+            .clone();
+        let restart_required = restart_required(&restart_baseline, &candidate);
+        self.persist(candidate).await?;
+        self.history.lock().await.record(&before, &after).await;
+        self.broadcast(&before, &after, restart_required);
+        Ok(())
+    }
+
+    /// Notify subscribers of the changes from `before` to `after`, if any.
+    fn broadcast(&self, before: &Value, after: &Value, restart_required: bool) {
+        let mut changes = Vec::new();
+        changed_values(before, after, &mut Vec::new(), &mut changes);
+        if changes.is_empty() {
+            return;
+        }
+        // Fails only without subscribers.
+        self.events
+            .send(ConfigChangedEvent {
+                changes,
+                restart_required,
+            })
+            .ok();
+    }
+
+    /// Write the changes from the persisted configuration to the file, and commit `candidate`.
+    ///
+    /// Must be called with the writer lock held.
+    async fn persist(&self, candidate: Config) -> Result<(), ConfigError> {
+        let persisted = self.persisted.read().expect("config lock poisoned").clone();
+        candidate
+            .update_file(&persisted, &self.config_file)
+            .await
+            .map_err(|e| ConfigError::Persistence(e.to_string()))?;
+
+        let candidate = Arc::new(candidate);
+        *self.persisted.write().expect("config lock poisoned") = candidate.clone();
+        *self.current.write().expect("config lock poisoned") = candidate;
+        Ok(())
+    }
 }
 
 /// Tauri-managed application state.
@@ -367,32 +654,97 @@ pub fn normalize_config(mut config: Config) -> Config {
     config
 }
 
+/// JSON Pointer to a dotted configuration key, like `memos.port`.
+pub fn json_pointer(key: &str) -> String {
+    key.split('.')
+        .map(|part| format!("/{}", part.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// Collect the leaf values that differ between `before` and `after`.
+fn changed_values(
+    before: &Value,
+    after: &Value,
+    path: &mut Vec<String>,
+    changes: &mut Vec<ConfigValueChange>,
+) {
+    match (before, after) {
+        (Value::Object(before_map), Value::Object(after_map)) => {
+            let mut keys: Vec<&String> = before_map.keys().chain(after_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                path.push(key.clone());
+                changed_values(
+                    before_map.get(key).unwrap_or(&Value::Null),
+                    after_map.get(key).unwrap_or(&Value::Null),
+                    path,
+                    changes,
+                );
+                path.pop();
+            }
+        }
+        _ if before == after => {}
+        _ => changes.push(ConfigValueChange {
+            key: path.join("."),
+            path: path
+                .iter()
+                .map(|part| format!("/{}", part.replace('~', "~0").replace('/', "~1")))
+                .collect(),
+            old: before.clone(),
+            new: after.clone(),
+        }),
+    }
+}
+
+/// Three-way merge of external changes into the current configuration.
+///
+/// `base` is the configuration the file had before the external change. Values changed in
+/// `external` win. Those also changed to a different value in `current` are recorded in
+/// `conflicts`.
+fn merge_external(
+    base: &Value,
+    current: &Value,
+    external: &Value,
+    path: &mut Vec<String>,
+    conflicts: &mut Vec<String>,
+) -> Value {
+    if external == base {
+        return current.clone();
+    }
+    if current == base || current == external {
+        return external.clone();
+    }
+    match (base, current, external) {
+        (Value::Object(base), Value::Object(current), Value::Object(external)) => {
+            let mut merged = serde_json::Map::new();
+            for (key, external) in external {
+                path.push(key.clone());
+                let value = match (base.get(key), current.get(key)) {
+                    (Some(base), Some(current)) => {
+                        merge_external(base, current, external, path, conflicts)
+                    }
+                    _ => external.clone(),
+                };
+                merged.insert(key.clone(), value);
+                path.pop();
+            }
+            Value::Object(merged)
+        }
+        _ => {
+            conflicts.push(path.join("."));
+            external.clone()
+        }
+    }
+}
+
 /// Whether changing from `before` to `after` requires restarting Memospot.
 ///
+/// Fields are classified in the `config` crate, with the `x-restart` schema keyword.
 /// Server/process settings and startup-only window settings are restart
 /// required. Theme, reduce-animation, and locale changes apply live.
 pub fn restart_required(before: &Config, after: &Config) -> bool {
-    if before.memos != after.memos
-        || before.memospot.remote != after.memospot.remote
-        || before.memospot.env != after.memospot.env
-        || before.memospot.updater != after.memospot.updater
-        || before.memospot.log != after.memospot.log
-        || before.memospot.migrations != after.memospot.migrations
-        || before.memospot.backups != after.memospot.backups
-    {
-        return true;
-    }
-    let before_window = &before.memospot.window;
-    let after_window = &after.memospot.window;
-    before_window.center != after_window.center
-        || before_window.fullscreen != after_window.fullscreen
-        || before_window.resizable != after_window.resizable
-        || before_window.maximized != after_window.maximized
-        || before_window.width != after_window.width
-        || before_window.height != after_window.height
-        || before_window.x != after_window.x
-        || before_window.y != after_window.y
-        || before_window.hide_menu_bar != after_window.hide_menu_bar
+    before.restart_required(after)
 }
 
 /// Apply debug-only Memos server mode and port overrides.
@@ -419,6 +771,11 @@ pub fn apply_debug_overrides(memos: &mut config::Memos) {
 enum ExportTSBindings {
     Config(Config),
     ConfigUpdateResult(ConfigUpdateResult),
+    ConfigReloadResult(ConfigReloadResult),
+    ConfigImportResult(ConfigImportResult),
+    ConfigChange(ConfigChange),
+    ConfigChangedEvent(ConfigChangedEvent),
+    Provenance(config::Provenance),
 }
 
 #[cfg(test)]
@@ -550,6 +907,12 @@ mod tests {
         after = before.clone();
         after.memospot.updater.enabled = Some(false);
         assert!(restart_required(&before, &after));
+        after = before.clone();
+        after.memospot.quick_capture.shortcut = Some("Alt+Space".into());
+        assert!(restart_required(&before, &after));
+        after = before.clone();
+        after.memospot.quick_capture.access_token = Some("token".into());
+        assert!(!restart_required(&before, &after));
 
         // Startup-only window settings.
         after = before.clone();
@@ -558,6 +921,12 @@ mod tests {
         after = before.clone();
         after.memospot.window.hide_menu_bar = Some(true);
         assert!(restart_required(&before, &after));
+        after = before.clone();
+        after.memospot.window.tray_icon = Some(true);
+        assert!(restart_required(&before, &after));
+        after = before.clone();
+        after.memospot.window.close_to_tray = Some(true);
+        assert!(restart_required(&before, &after));
     }
 
     #[tokio::test]
@@ -648,6 +1017,148 @@ mod tests {
         assert_eq!(store.snapshot().current, before);
     }
 
+    #[tokio::test]
+    async fn policy_keys_are_read_only() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = default_store(&dir)
+            .with_read_only(vec!["memospot.remote".into(), "memos.port".into()]);
+        let config_file = dir.path().join("memospot.yaml");
+
+        let error = store
+            .apply_patch_and_persist(&patch("/memospot/remote/url", json!("https://a.b/")))
+            .await
+            .expect_err("patch should fail");
+        assert!(matches!(error, ConfigError::ReadOnly(key) if key == "memospot.remote"));
+
+        let error = store
+            .update_and_persist(|config| config.memos.port = Some(9999))
+            .await
+            .expect_err("update should fail");
+        assert!(matches!(error, ConfigError::ReadOnly(key) if key == "memos.port"));
+        assert!(!config_file.exists());
+
+        store
+            .apply_patch_and_persist(&patch("/memospot/window/theme", json!("dark")))
+            .await
+            .expect("other keys remain editable");
+    }
+
+    #[tokio::test]
+    async fn import_reports_changes_and_skips_policy_keys() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = default_store(&dir).with_read_only(vec!["memospot.remote.url".into()]);
+        let config_file = dir.path().join("memospot.yaml");
+        let document = "memos:\n  port: 4321\nmemospot:\n  remote:\n    url: https://a.b/\n";
+
+        let preview = store.import(document, true).await.expect("dry run");
+        assert!(!preview.result.applied);
+        assert!(preview.result.restart_required);
+        let paths: Vec<String> = preview
+            .result
+            .patch
+            .iter()
+            .map(|op| op.path().to_string())
+            .collect();
+        assert_eq!(paths, ["/memos/port"]);
+        assert_eq!(
+            store.snapshot().current.memos.port,
+            Config::default().memos.port
+        );
+        assert!(!config_file.exists());
+
+        let import = store.import(document, false).await.expect("import");
+        assert!(import.result.applied);
+        let current = store.snapshot().current;
+        assert_eq!(current.memos.port, Some(4321));
+        assert_eq!(
+            current.memospot.remote.url,
+            Config::default().memospot.remote.url
+        );
+        assert!(config_file.exists());
+
+        store
+            .import("memos:\n  port: not-a-port\n", false)
+            .await
+            .expect_err("invalid documents are rejected");
+    }
+
+    #[tokio::test]
+    async fn changes_are_recorded_and_can_be_reverted() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = default_store(&dir);
+
+        store
+            .apply_patch_and_persist(&patch("/memos/data", json!("/mnt/wrong")))
+            .await
+            .expect("patch should succeed");
+        store
+            .update_and_persist(|config| config.memospot.window.theme = Some("dark".into()))
+            .await
+            .expect("update should succeed");
+        // Runtime-owned fields are not recorded.
+        store
+            .update_runtime_owned_fields(|config| config.memospot.window.width = Some(640))
+            .await;
+        store.finalize_persistence().await.expect("finalize");
+
+        let history = store.history().await;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].id, 1);
+        assert_eq!(history[0].patch[0].path().as_str(), "/memos/data");
+
+        let result = store.revert(1).await.expect("revert should succeed");
+        // Back to the startup value.
+        assert!(!result.result.restart_required);
+        let current = store.snapshot().current;
+        assert_eq!(current.memos.data, Config::default().memos.data);
+        assert_eq!(current.memospot.window.theme.as_deref(), Some("dark"));
+
+        // The revert is recorded, and the history survives restarts.
+        let reloaded = default_store(&dir);
+        assert_eq!(reloaded.history().await.len(), 3);
+        assert!(matches!(
+            reloaded.revert(42).await,
+            Err(ConfigError::UnknownChange(42))
+        ));
+    }
+
+    #[tokio::test]
+    async fn changes_are_broadcast_to_subscribers() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = default_store(&dir);
+        let mut events = store.subscribe();
+
+        store
+            .apply_patch_and_persist(&patch("/memos/port", json!(5231)))
+            .await
+            .expect("patch should succeed");
+        let event = events.try_recv().expect("patch should be broadcast");
+        assert!(event.restart_required);
+        assert_eq!(event.changes.len(), 1);
+        assert_eq!(event.changes[0].key, "memos.port");
+        assert_eq!(event.changes[0].path, "/memos/port");
+        assert_eq!(event.changes[0].old, json!(Config::default().memos.port));
+        assert_eq!(event.changes[0].new, json!(5231));
+
+        // Runtime-owned updates and no-op patches are not broadcast.
+        store
+            .update_runtime_owned_fields(|config| config.memospot.window.width = Some(640))
+            .await;
+        store
+            .apply_patch_and_persist(&patch("/memos/port", json!(5231)))
+            .await
+            .expect("patch should succeed");
+        assert!(events.try_recv().is_err());
+
+        let mut external = (*store.snapshot().current).clone();
+        external.memospot.window.theme = Some("dark".into());
+        store.reload(external).await.expect("reload should succeed");
+        let event = events.try_recv().expect("reload should be broadcast");
+        assert_eq!(event.changes.len(), 1);
+        assert_eq!(event.changes[0].key, "memospot.window.theme");
+        assert_eq!(event.changes[0].new, json!("dark"));
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
     async fn concurrent_updates_to_different_fields_are_all_retained() {
         let dir = TempDir::new().expect("tempdir");
@@ -810,4 +1321,70 @@ mod tests {
         assert_eq!(window.x, Some(42));
         assert_eq!(window.y, Some(24));
     }
+
+    #[tokio::test]
+    async fn reload_ignores_the_store_own_writes() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = default_store(&dir);
+
+        store
+            .apply_patch_and_persist(&patch("/memospot/window/theme", json!("dark")))
+            .await
+            .expect("patch should succeed");
+
+        let on_disk = Config::init(&dir.path().join("memospot.yaml")).expect("config file");
+        assert_eq!(store.reload(on_disk).await.expect("reload"), None);
+    }
+
+    #[tokio::test]
+    async fn reload_merges_external_changes_and_reports_conflicts() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = default_store(&dir);
+
+        // Unsaved in-app changes.
+        store
+            .update_runtime_owned_fields(|config| {
+                config.memospot.window.width = Some(1440);
+                config.memospot.window.height = Some(900);
+            })
+            .await;
+
+        let mut external = Config::default();
+        external.memospot.window.width = Some(1600);
+        external.memospot.window.locale = Some("es".to_string());
+        let reload = store
+            .reload(external)
+            .await
+            .expect("reload")
+            .expect("changes should be merged");
+
+        assert_eq!(reload.result.conflicts, ["memospot.window.width"]);
+        assert!(reload.locale_changed);
+        // The window size only applies on restart.
+        assert!(reload.result.restart_required);
+
+        let snapshot = store.snapshot();
+        let window = &snapshot.current.memospot.window;
+        assert_eq!(window.width, Some(1600));
+        assert_eq!(window.height, Some(900));
+        assert_eq!(window.locale.as_deref(), Some("es"));
+    }
+
+    #[tokio::test]
+    async fn reload_flags_restart_required_settings() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = default_store(&dir);
+
+        let mut external = Config::default();
+        external.memos.port = Some(9999);
+        let reload = store
+            .reload(external)
+            .await
+            .expect("reload")
+            .expect("changes should be merged");
+
+        assert!(reload.result.restart_required);
+        assert!(reload.result.conflicts.is_empty());
+        assert_eq!(store.snapshot().current.memos.port, Some(9999));
+    }
 }
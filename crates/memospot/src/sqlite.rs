@@ -1,7 +1,11 @@
+use crate::zip;
 use anyhow::{Result, anyhow};
 use log::{debug, warn};
 use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Database files that must travel together with the main database file.
+const RELATED_EXTENSIONS: [&str; 2] = ["db-wal", "db-shm"];
 
 /// Get a database connection using SeaORM.
 pub async fn get_database_connection(db: &Path) -> Result<DatabaseConnection, anyhow::Error> {
@@ -86,3 +90,30 @@ pub async fn wait_checkpoint(db_file: &Path) {
         }
     }
 }
+
+/// Back up the database and its related files into `backup_dir`.
+///
+/// The archive is named `db-{datetime}-{label}.zst.zip`. Returns the archive path.
+pub async fn backup(db_file: &Path, backup_dir: &Path, label: &str) -> Result<PathBuf> {
+    let datetime = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let backup_path = backup_dir.join(format!("db-{datetime}-{label}.zst.zip"));
+    zip::related_files(db_file, &RELATED_EXTENSIONS, &backup_path).await?;
+    Ok(backup_path)
+}
+
+/// Replace the database with the contents of a backup created by [`backup`].
+///
+/// Related files missing from the archive are removed afterwards, so stale WAL
+/// pages are never replayed over the restored database.
+///
+/// The database must not be in use.
+pub async fn restore(backup_zip: &Path, db_file: &Path) -> Result<()> {
+    let written = zip::extract_related(backup_zip, &RELATED_EXTENSIONS, db_file).await?;
+    for ext in RELATED_EXTENSIONS {
+        let related = db_file.with_extension(ext);
+        if !written.contains(&related) && related.exists() {
+            tokio::fs::remove_file(&related).await?;
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,16 @@
+/// Keep the vault key of every test in the same temporary directory.
+fn use_test_key_dir() {
+    static KEY_DIR: std::sync::OnceLock<tempfile::TempDir> = std::sync::OnceLock::new();
+    KEY_DIR.get_or_init(|| {
+        let dir = tempfile::tempdir().expect("tempdir");
+        // SAFETY: Set once, before any vault is opened.
+        unsafe {
+            std::env::set_var("MEMOSPOT_SECRETS_KEY_DIR", dir.path());
+        }
+        dir
+    });
+}
+
 mod configuration_state_tests {
     use crate::cmd;
     use crate::i18n;
@@ -191,4 +204,381 @@ mod memos_tests {
         assert_eq!(memos.mode, Some("prod".to_string()));
         assert_eq!(memos.demo, Some(false));
     }
+
+    #[test]
+    fn prepare_env_resolves_secrets() {
+        use crate::memos::{prepare_env, secret_env_keys};
+        use crate::runtime_config::{ActiveServer, RuntimeContext, RuntimePaths};
+        use config::SecretVault;
+        use std::collections::HashMap;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config_file = dir.path().join("memospot.yaml");
+        super::use_test_key_dir();
+        let mut vault = SecretVault::open(&config_file).expect("vault");
+        vault.set("s3_key", "hunter2").expect("set secret");
+
+        let mut memos = config::Memos::default();
+        memos.env.enabled = Some(true);
+        memos.env.vars = Some(HashMap::from([
+            ("MEMOS_S3_KEY".to_string(), "secret:s3_key".to_string()),
+            ("MEMOS_S3_OTHER".to_string(), "secret:missing".to_string()),
+            ("MEMOS_PLAIN".to_string(), "value".to_string()),
+        ]));
+        let runtime = RuntimeContext {
+            paths: RuntimePaths {
+                memos_bin: Default::default(),
+                memos_data: dir.path().to_path_buf(),
+                memos_db_file: Default::default(),
+                memospot_bin: Default::default(),
+                memospot_config_file: config_file,
+                memospot_cwd: Default::default(),
+                memospot_data: dir.path().to_path_buf(),
+            },
+            active_server: ActiveServer {
+                url: "http://localhost:5230/".into(),
+                user_agent: "test".into(),
+                managed: true,
+            },
+            memos,
+        };
+
+        let env = prepare_env(&runtime);
+        assert_eq!(env.get("MEMOS_S3_KEY").map(String::as_str), Some("hunter2"));
+        assert_eq!(env.get("MEMOS_PLAIN").map(String::as_str), Some("value"));
+        // Unresolved references are left out, instead of passing the reference along.
+        assert!(!env.contains_key("MEMOS_S3_OTHER"));
+
+        let secret_keys = secret_env_keys(&runtime.memos);
+        assert!(secret_keys.contains("MEMOS_S3_KEY"));
+        assert!(!secret_keys.contains("MEMOS_PLAIN"));
+
+        let mut memos = runtime.memos;
+        memos.env.enabled = Some(false);
+        assert!(secret_env_keys(&memos).is_empty());
+    }
+}
+
+mod capture_tests {
+    use crate::capture::access_token;
+    use config::{QuickCapture, SecretVault};
+
+    #[test]
+    fn access_token_resolves_secrets() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config_file = dir.path().join("memospot.yaml");
+        super::use_test_key_dir();
+        let mut vault = SecretVault::open(&config_file).expect("vault");
+        vault
+            .set("memos_token", "memos_pat_secret")
+            .expect("set secret");
+
+        let mut settings = QuickCapture {
+            access_token: Some("secret:memos_token".into()),
+            ..Default::default()
+        };
+        let token = access_token(&settings, &config_file).expect("resolved token");
+        assert_eq!(token, "memos_pat_secret");
+
+        settings.access_token = Some("memos_pat_plain".into());
+        let token = access_token(&settings, &config_file).expect("plain token");
+        assert_eq!(token, "memos_pat_plain");
+
+        settings.access_token = Some("secret:missing".into());
+        assert!(access_token(&settings, &config_file).is_err());
+        settings.access_token = None;
+        assert!(access_token(&settings, &config_file).is_err());
+    }
+}
+
+mod cli_tests {
+    use crate::cli::Command;
+    use std::path::PathBuf;
+
+    fn parse(args: &[&str]) -> Option<anyhow::Result<Command>> {
+        Command::parse(args)
+    }
+
+    #[test]
+    fn parse_ignores_unknown_arguments() {
+        assert!(parse(&[]).is_none());
+        assert!(parse(&["-psn_0_12345"]).is_none());
+        assert!(parse(&["memospot://open"]).is_none());
+    }
+
+    #[test]
+    fn parse_commands() {
+        let cases = [
+            (vec!["help"], Command::Help),
+            (vec!["backup"], Command::Backup { output: None }),
+            (
+                vec!["backup", "--output", "/tmp/backups"],
+                Command::Backup {
+                    output: Some(PathBuf::from("/tmp/backups")),
+                },
+            ),
+            (
+                vec!["restore", "db.zst.zip"],
+                Command::Restore {
+                    input: PathBuf::from("db.zst.zip"),
+                },
+            ),
+            (
+                vec!["migrate"],
+                Command::Migrate {
+                    dry_run: false,
+                    json: false,
+                },
+            ),
+            (
+                vec!["migrate", "--json", "--dry-run"],
+                Command::Migrate {
+                    dry_run: true,
+                    json: true,
+                },
+            ),
+            (vec!["checkpoint"], Command::Checkpoint),
+            (vec!["diagnostics"], Command::Diagnostics { output: None }),
+            (
+                vec!["diagnostics", "-o", "/tmp"],
+                Command::Diagnostics {
+                    output: Some(PathBuf::from("/tmp")),
+                },
+            ),
+            (vec!["config", "get"], Command::ConfigGet { key: None }),
+            (
+                vec!["config", "get", "memos.port"],
+                Command::ConfigGet {
+                    key: Some("memos.port".into()),
+                },
+            ),
+            (
+                vec!["config", "set", "memos.port", "5231"],
+                Command::ConfigSet {
+                    key: "memos.port".into(),
+                    value: "5231".into(),
+                },
+            ),
+            (
+                vec!["config", "origin"],
+                Command::ConfigOrigin { key: None },
+            ),
+            (
+                vec!["config", "origin", "memos"],
+                Command::ConfigOrigin {
+                    key: Some("memos".into()),
+                },
+            ),
+            (
+                vec!["config", "export"],
+                Command::ConfigExport {
+                    json: false,
+                    keep_machine: false,
+                    keep_secrets: false,
+                },
+            ),
+            (
+                vec!["config", "export", "--json", "--keep-secrets"],
+                Command::ConfigExport {
+                    json: true,
+                    keep_machine: false,
+                    keep_secrets: true,
+                },
+            ),
+            (
+                vec!["config", "import", "shared.yaml", "--dry-run"],
+                Command::ConfigImport {
+                    input: PathBuf::from("shared.yaml"),
+                    dry_run: true,
+                },
+            ),
+            (vec!["config", "history"], Command::ConfigHistory),
+            (
+                vec!["config", "revert", "3"],
+                Command::ConfigRevert { id: 3 },
+            ),
+            (vec!["config", "validate"], Command::ConfigValidate),
+            (vec!["secret", "list"], Command::SecretList),
+            (
+                vec!["secret", "set", "s3_key"],
+                Command::SecretSet {
+                    name: "s3_key".into(),
+                    value: None,
+                },
+            ),
+            (
+                vec!["secret", "set", "s3_key", "hunter2"],
+                Command::SecretSet {
+                    name: "s3_key".into(),
+                    value: Some("hunter2".into()),
+                },
+            ),
+            (
+                vec!["secret", "delete", "s3_key"],
+                Command::SecretDelete {
+                    name: "s3_key".into(),
+                },
+            ),
+            (vec!["server", "start"], Command::ServerStart),
+            (vec!["server", "stop"], Command::ServerStop),
+            (vec!["server", "status"], Command::ServerStatus),
+            (vec!["--headless"], Command::Headless),
+        ];
+
+        for (args, expected) in cases {
+            let parsed = parse(&args)
+                .expect("command should be recognized")
+                .unwrap_or_else(|e| panic!("{args:?} should parse: {e}"));
+            assert_eq!(parsed, expected);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid_usage() {
+        for args in [
+            vec!["checkpoint", "now"],
+            vec!["diagnostics", "--output"],
+            vec!["backup", "--output"],
+            vec!["restore"],
+            vec!["restore", "--force"],
+            vec!["migrate", "--json"],
+            vec!["config"],
+            vec!["config", "set", "memos.port"],
+            vec!["config", "export", "--yaml"],
+            vec!["config", "import"],
+            vec!["config", "import", "shared.yaml", "--force"],
+            vec!["config", "revert", "last"],
+            vec!["secret"],
+            vec!["secret", "delete"],
+            vec!["server"],
+            vec!["server", "restart"],
+            vec!["--headless", "--verbose"],
+        ] {
+            let parsed = parse(&args).expect("command should be recognized");
+            assert!(parsed.is_err(), "{args:?} should be rejected");
+        }
+    }
+}
+
+mod diagnostics_tests {
+    use crate::diagnostics::{redact_config, redact_log};
+    use config::Config;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn redact_config_hides_secrets() {
+        let mut config = Config::default();
+        config.memospot.quick_capture.access_token = Some("memos_pat_secret".into());
+        config.memos.env.vars = Some(HashMap::from([("MEMOS_DRIVER".into(), "sqlite".into())]));
+        config.memospot.env.vars = Some(HashMap::from([("HTTP_PROXY".into(), "proxy".into())]));
+        let patterns = config
+            .memospot
+            .diagnostics
+            .redact_keys
+            .clone()
+            .unwrap_or_default();
+
+        let redacted = redact_config(&config, &patterns, true).unwrap();
+        assert_eq!(
+            redacted.memospot.quick_capture.access_token.as_deref(),
+            Some("[REDACTED]")
+        );
+        assert_eq!(
+            redacted.memos.env.vars.unwrap()["MEMOS_DRIVER"],
+            "[REDACTED]"
+        );
+        assert_eq!(
+            redacted.memospot.env.vars.unwrap()["HTTP_PROXY"],
+            "[REDACTED]"
+        );
+        assert_eq!(redacted.memos.addr, config.memos.addr);
+        assert_eq!(
+            redacted.memospot.diagnostics.redact_keys,
+            config.memospot.diagnostics.redact_keys
+        );
+
+        let kept = redact_config(&config, &patterns, false).unwrap();
+        assert_eq!(kept.memos.env.vars, config.memos.env.vars);
+    }
+
+    #[test]
+    fn redact_log_hides_env_values_and_secrets() {
+        let log = [
+            "DEBUG setting environment variable: HTTP_PROXY=http://proxy:3128",
+            "DEBUG setting environment variable: S3_TOKEN=abc123",
+            "DEBUG environment: {",
+            r#"    "MEMOS_DRIVER": "postgres","#,
+            r#"    "MEMOS_PORT": "5230","#,
+            "}",
+            "INFO connecting with abc123",
+            "",
+        ]
+        .join("\n");
+        let keys = HashSet::from(["HTTP_PROXY".to_string(), "MEMOS_DRIVER".to_string()]);
+        let patterns = ["token".to_string()];
+
+        let redacted = redact_log(&log, &keys, &patterns, &["abc123".to_string()]);
+        let expected = [
+            "DEBUG setting environment variable: HTTP_PROXY=[REDACTED]",
+            "DEBUG setting environment variable: S3_TOKEN=[REDACTED]",
+            "DEBUG environment: {",
+            r#"    "MEMOS_DRIVER": "[REDACTED]","#,
+            r#"    "MEMOS_PORT": "5230","#,
+            "}",
+            "INFO connecting with [REDACTED]",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(redacted, expected);
+    }
+}
+
+mod sqlite_tests {
+    use crate::sqlite;
+    use std::fs;
+
+    #[tokio::test]
+    async fn backup_and_restore_related_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backup_dir = dir.path().join("backups");
+        fs::create_dir(&backup_dir).unwrap();
+
+        let source = dir.path().join("memos_demo.db");
+        fs::write(&source, b"database").unwrap();
+        fs::write(source.with_extension("db-wal"), b"wal").unwrap();
+
+        let backup = sqlite::backup(&source, &backup_dir, "manual")
+            .await
+            .expect("backup should succeed");
+        let name = backup.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("db-") && name.ends_with("-manual.zst.zip"));
+
+        // Restore over a database with a different name and a stale shared-memory file.
+        let target = dir.path().join("memos_prod.db");
+        fs::write(&target, b"newer").unwrap();
+        fs::write(target.with_extension("db-shm"), b"stale").unwrap();
+
+        sqlite::restore(&backup, &target)
+            .await
+            .expect("restore should succeed");
+
+        assert_eq!(fs::read(&target).unwrap(), b"database");
+        assert_eq!(fs::read(target.with_extension("db-wal")).unwrap(), b"wal");
+        assert!(!target.with_extension("db-shm").exists());
+    }
+
+    #[tokio::test]
+    async fn restore_rejects_archive_without_database() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let wal = dir.path().join("memos_prod.db-wal");
+        fs::write(&wal, b"wal").unwrap();
+        let archive = dir.path().join("wal-only.zst.zip");
+        crate::zip::related_files(&wal, &[], &archive)
+            .await
+            .unwrap();
+
+        let target = dir.path().join("restored").join("memos_prod.db");
+        assert!(sqlite::restore(&archive, &target).await.is_err());
+        assert!(!target.exists());
+    }
 }
@@ -0,0 +1,137 @@
+//! System tray icon.
+//!
+//! The icon is declared in `Tauri.toml` (`app.trayIcon`) and created by Tauri on startup.
+//! [`setup`] removes it unless it's enabled in the configuration, and attaches the menu.
+//!
+//! Menu events are handled by [`crate::event::handle_menu_event`], along with the main menu.
+
+use crate::fl;
+use crate::menu::MainMenu;
+use crate::runtime_config::AppState;
+use crate::window::Window;
+use crate::{init, memos, sqlite};
+use config::Config;
+use dialog::*;
+use log::{debug, info};
+use tauri::menu::{Menu, MenuItemBuilder, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconEvent};
+use tauri::{AppHandle, Manager, Runtime, async_runtime};
+
+/// Tray icon id, as declared in `Tauri.toml`.
+const TRAY_ID: &str = "main";
+
+/// Whether the tray icon should be shown.
+pub fn is_enabled(config: &Config) -> bool {
+    let window = &config.memospot.window;
+    window.tray_icon == Some(true) || window.close_to_tray == Some(true)
+}
+
+/// Whether closing the main window should hide it to the tray instead of exiting.
+pub fn should_close_to_tray<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let config = app.state::<AppState>().config.snapshot().current;
+    config.memospot.window.close_to_tray == Some(true) && app.tray_by_id(TRAY_ID).is_some()
+}
+
+/// Set up the tray icon created from `Tauri.toml`, or remove it if disabled.
+pub fn setup<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    if !is_enabled(&app.state::<AppState>().config.snapshot().current) {
+        debug!("tray icon is disabled");
+        app.remove_tray_by_id(TRAY_ID);
+        return Ok(());
+    }
+
+    tray.set_menu(Some(build_menu(app)?))?;
+    tray.on_tray_icon_event(|tray, event| {
+        if let TrayIconEvent::Click {
+            button: MouseButton::Left,
+            button_state: MouseButtonState::Up,
+            ..
+        } = event
+        {
+            show_main_window(tray.app_handle());
+        }
+    });
+    Ok(())
+}
+
+fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let managed = app.state::<AppState>().runtime.active_server.managed;
+    let item = |menu: MainMenu| MenuItemBuilder::with_id(menu.id(), menu.text());
+
+    Menu::with_items(
+        app,
+        &[
+            &item(MainMenu::TrayShowWindow).build(app)?,
+            &item(MainMenu::AppOpenInBrowser).build(app)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(MainMenu::TrayRestartServer)
+                .enabled(managed)
+                .build(app)?,
+            &item(MainMenu::TrayBackupNow).enabled(managed).build(app)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(MainMenu::AppQuit).build(app)?,
+        ],
+    )
+}
+
+/// Show, unminimize and focus the main window.
+pub fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(Window::Main.into()) {
+        window.show().ok();
+        window.unminimize().ok();
+        window.set_focus().ok();
+    }
+}
+
+/// Restart the managed Memos server and reload the main window once it's ready.
+pub fn restart_server<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let runtime = &state.runtime;
+        if !runtime.active_server.managed {
+            return;
+        }
+
+        info!("restarting Memos server…");
+        memos::shutdown(runtime).await;
+        let config = state.config.snapshot().current;
+        if let Err(e) = memos::spawn(runtime, &config) {
+            error_dialog!(fl!("error-failed-to-restart-memos", error = e.to_string()));
+            return;
+        }
+        memos::wait_api_ready(&runtime.active_server.url).await;
+
+        if let Some(window) = app.get_webview_window(Window::Main.into()) {
+            window.reload().ok();
+        }
+    });
+}
+
+/// Back up the database to the configured backup directory.
+pub fn backup_now<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let paths = &state.runtime.paths;
+        let config = state.config.snapshot().current;
+        let backup_dir = init::ensure_backup_directory(&config, &paths.memospot_data);
+
+        // Flush the WAL first, so the archive is as self-contained as possible.
+        sqlite::checkpoint(&paths.memos_db_file).await.ok();
+        match sqlite::backup(&paths.memos_db_file, &backup_dir, "manual").await {
+            Ok(backup) => {
+                info_dialog!(fl!(
+                    "info-backup-completed",
+                    file = backup.to_string_lossy()
+                ));
+            }
+            Err(e) => {
+                warn_dialog!(fl!("warn-failed-to-backup-database", error = e.to_string()));
+            }
+        }
+    });
+}
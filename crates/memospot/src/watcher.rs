@@ -0,0 +1,110 @@
+//! Configuration file watcher.
+//!
+//! External edits to `memospot.yaml` are merged into the configuration store while the app
+//! runs, instead of being overwritten at shutdown. Live settings apply immediately; the
+//! frontend is notified through [`CONFIG_RELOADED_EVENT`], along with whether a restart
+//! is required and which unsaved in-app changes were replaced.
+
+use crate::cmd;
+use crate::runtime_config::AppState;
+use config::Config;
+use log::{debug, info, warn};
+use notify::{Event, RecursiveMode, Watcher};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, async_runtime};
+
+/// Emitted with a `ConfigReloadResult` after merging external changes.
+pub(crate) const CONFIG_RELOADED_EVENT: &str = "memospot-config-reloaded";
+
+/// Editors may write a file in several steps. Wait for them to settle before reloading.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watch the configuration file for external changes.
+pub fn setup<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<AppState>();
+    let config_file = state.runtime.paths.memospot_config_file.clone();
+    let (Some(config_dir), Some(file_name)) = (config_file.parent(), config_file.file_name())
+    else {
+        return;
+    };
+
+    // Watch the directory, as the file may be replaced rather than modified in place.
+    let file_name = file_name.to_owned();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        if let Ok(event) = event
+            && !event.kind.is_access()
+            && event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == Some(file_name.as_os_str()))
+        {
+            tx.send(()).ok();
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!("unable to watch the configuration file: {e}");
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(config_dir, RecursiveMode::NonRecursive) {
+        warn!("unable to watch {}: {e}", config_dir.display());
+        return;
+    }
+    debug!("watching {} for changes", config_file.display());
+
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        let _watcher = watcher;
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+            reload(&app).await;
+        }
+    });
+}
+
+/// Merge the configuration file into the store, and apply live settings.
+async fn reload<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<AppState>();
+    let config_file = &state.runtime.paths.memospot_config_file;
+    if !config_file.is_file() {
+        return;
+    }
+
+    let external = match Config::init_validated(config_file) {
+        Ok((config, errors)) => {
+            for error in errors {
+                warn!("configuration file: {error}");
+            }
+            config
+        }
+        Err(e) => {
+            warn!("ignoring configuration file change: {e}");
+            return;
+        }
+    };
+
+    let reload = match state.config.reload(external).await {
+        Ok(Some(reload)) => reload,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("failed to reload configuration: {e}");
+            return;
+        }
+    };
+    info!("configuration reloaded from {}", config_file.display());
+    for key in &reload.result.conflicts {
+        warn!("configuration reload: unsaved change to `{key}` replaced by the file's value");
+    }
+    if reload.result.restart_required {
+        info!("configuration reload: restart Memospot to apply all changes");
+    }
+
+    if reload.locale_changed {
+        cmd::apply_locale(app, &state);
+    }
+    app.emit(CONFIG_RELOADED_EVENT, reload.result).ok();
+}
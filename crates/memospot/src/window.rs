@@ -10,6 +10,8 @@ pub enum Window {
     Main,
     #[strum(serialize = "settings")]
     Settings,
+    #[strum(serialize = "capture")]
+    Capture,
 }
 
 impl Window {
@@ -1,5 +1,7 @@
 use anyhow::Result;
+use anyhow::bail;
 use async_zip::base::write::ZipFileWriter;
+use async_zip::tokio::read::fs::ZipFileReader;
 use async_zip::tokio::write::ZipFileWriter as TokioZipFileWriter;
 use async_zip::{Compression, ZipEntryBuilder};
 use log::debug;
@@ -49,6 +51,82 @@ pub async fn related_files(
     Ok(())
 }
 
+/// Extract a zip file created by [`related_files`].
+///
+/// Entries are written next to `output_file` and renamed after it, keeping their
+/// extensions, so an archive can be restored over a file with a different name.
+/// Entries with extensions other than the main file's or `related_extensions` are skipped.
+///
+/// Returns the written files. Fails if the main file is not present in the archive.
+pub async fn extract_related(
+    input_zip: &Path,
+    related_extensions: &[&str],
+    output_file: &Path,
+) -> Result<Vec<PathBuf>> {
+    debug!("extracting file: {}", input_zip.to_string_lossy());
+    debug!("output file: {}", output_file.to_string_lossy());
+
+    let main_extension = output_file
+        .extension()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let reader = ZipFileReader::new(input_zip).await?;
+
+    let mut targets: Vec<(usize, PathBuf)> = Vec::new();
+    for (index, entry) in reader.file().entries().iter().enumerate() {
+        let filename = entry.filename().as_str()?;
+        let Some(extension) = Path::new(filename).extension() else {
+            debug!("skipping entry without extension '{filename}'");
+            continue;
+        };
+        let extension = extension.to_string_lossy().to_string();
+        if extension != main_extension && !related_extensions.contains(&extension.as_str()) {
+            debug!("skipping unrelated entry '{filename}'");
+            continue;
+        }
+        targets.push((index, output_file.with_extension(extension)));
+    }
+
+    if !targets.iter().any(|(_, path)| path == output_file) {
+        bail!(
+            "archive does not contain a `.{main_extension}` file: {}",
+            input_zip.to_string_lossy()
+        );
+    }
+
+    let mut written: Vec<PathBuf> = Vec::with_capacity(targets.len());
+    for (index, output_path) in targets {
+        let mut entry = reader.reader_with_entry(index).await?;
+        let mut buffer = Vec::new();
+        entry.read_to_end_checked(&mut buffer).await?;
+
+        debug!("writing file '{}'", output_path.to_string_lossy());
+        tokio::fs::write(&output_path, &buffer).await?;
+        written.push(output_path);
+    }
+
+    Ok(written)
+}
+
+/// Create a zip file from in-memory entries, as `(file name, contents)` pairs.
+///
+/// Entries are stored uncompressed, so the archive opens with any unzip tool.
+pub async fn from_entries(entries: &[(String, Vec<u8>)], output_zip: &Path) -> Result<()> {
+    debug!("output file: {}", output_zip.to_string_lossy());
+
+    let file = File::create(output_zip).await?;
+    let mut writer: TokioZipFileWriter<File> = ZipFileWriter::with_tokio(file);
+    for (filename, contents) in entries {
+        debug!("adding entry '{filename}'");
+        let builder = ZipEntryBuilder::new(filename.clone().into(), Compression::Stored);
+        writer.write_entry_whole(builder, contents).await?;
+    }
+    writer.close().await?;
+
+    Ok(())
+}
+
 /// Write a file to a zip writer.
 async fn write_entry(input_path: &Path, writer: &mut TokioZipFileWriter<File>) -> Result<()> {
     let mut input_file = File::open(input_path).await?;
@@ -0,0 +1,222 @@
+//! Preview pending migrations without touching the database.
+//!
+//! A consistent snapshot of the database is written to a temporary directory with
+//! `VACUUM INTO`, and each pending migration is applied to that copy, one at a time.
+//! Rows in `resource` and `system_setting` are captured before and after every step,
+//! so the report tells exactly what each migration would change.
+//!
+//! Notes:
+//! - The source database is opened read-only and is never modified.
+//! - Also used as a test harness: any `MigratorTrait` can be dry-run against a fixture.
+
+use log::{debug, info};
+use sea_orm::{
+    ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr, QueryResult, Statement,
+};
+use sea_orm_migration::{MigratorTrait, SchemaManager};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A `resource` row that would be created, deleted, or have its stored path changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceChange {
+    /// Resource id.
+    pub id: i64,
+    /// Path before the migration. `None` if the row would be created.
+    pub before: Option<String>,
+    /// Path after the migration. `None` if the row would be deleted.
+    pub after: Option<String>,
+}
+
+/// A `system_setting` entry that would be created, updated or removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SettingChange {
+    /// Setting name.
+    pub name: String,
+    /// Value before the migration. `None` if the setting would be created.
+    pub before: Option<String>,
+    /// Value after the migration. `None` if the setting would be removed.
+    pub after: Option<String>,
+}
+
+/// Changes made by a single migration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationChanges {
+    /// Migration name.
+    pub name: String,
+    /// Changed `resource.reference` (or legacy `resource.internal_path`) rows.
+    pub resources: Vec<ResourceChange>,
+    /// Changed `system_setting` values.
+    pub settings: Vec<SettingChange>,
+}
+
+impl MigrationChanges {
+    /// Whether this migration would leave the inspected data untouched.
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty() && self.settings.is_empty()
+    }
+}
+
+/// Result of a dry run, with one entry per pending migration, in execution order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DryRunReport {
+    pub migrations: Vec<MigrationChanges>,
+}
+
+impl DryRunReport {
+    /// Whether no pending migration would change the inspected data.
+    pub fn is_empty(&self) -> bool {
+        self.migrations.iter().all(MigrationChanges::is_empty)
+    }
+
+    /// Total amount of changed rows across all migrations.
+    pub fn len(&self) -> usize {
+        self.migrations
+            .iter()
+            .map(|m| m.resources.len() + m.settings.len())
+            .sum()
+    }
+}
+
+/// Data captured between migration steps.
+#[derive(Debug, Default)]
+struct Snapshot {
+    resources: BTreeMap<i64, String>,
+    settings: BTreeMap<String, String>,
+}
+
+/// Run all pending migrations of `M` against a temporary copy of `db_file`
+/// and report what would change.
+pub async fn dry_run<M: MigratorTrait>(db_file: &Path) -> Result<DryRunReport, DbErr> {
+    info!("::Database Migrator:: Dry run against a copy of {db_file:?}.");
+
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("memospot-dry-run-")
+        .tempdir()
+        .map_err(|e| DbErr::Custom(format!("failed to create temporary directory: {e}")))?;
+    let db_copy = tmp_dir.path().join("memos_dry_run.db");
+
+    let source =
+        Database::connect(format!("sqlite://{}?mode=ro", db_file.to_string_lossy())).await?;
+    let copy_path = db_copy.to_string_lossy().replace('\'', "''");
+    let vacuum = source
+        .execute_unprepared(&format!("VACUUM INTO '{copy_path}';"))
+        .await;
+    source.close().await?;
+    vacuum?;
+
+    let db =
+        Database::connect(format!("sqlite://{}?mode=rw", db_copy.to_string_lossy())).await?;
+    db.execute_unprepared("PRAGMA foreign_keys = 0;").await?;
+
+    let report = dry_run_connection::<M>(&db).await;
+    db.close().await?;
+    report
+}
+
+/// Run all pending migrations of `M` on `db`, one at a time, and report what changed.
+///
+/// The connection is migrated for real. Use [`dry_run`] to preserve the original database.
+pub async fn dry_run_connection<M: MigratorTrait>(
+    db: &DatabaseConnection,
+) -> Result<DryRunReport, DbErr> {
+    let pending = M::get_pending_migrations(db).await?;
+    debug!("dry run: {} pending migrations", pending.len());
+
+    let mut report = DryRunReport::default();
+    let mut before = snapshot(db).await?;
+    for migration in pending {
+        M::up(db, Some(1)).await?;
+        let after = snapshot(db).await?;
+
+        report.migrations.push(MigrationChanges {
+            name: migration.name().to_string(),
+            resources: diff_resources(&before, &after),
+            settings: diff_settings(&before, &after),
+        });
+        before = after;
+    }
+
+    info!(
+        "::Database Migrator:: Dry run finished. {} rows would change.",
+        report.len()
+    );
+    Ok(report)
+}
+
+async fn snapshot(db: &DatabaseConnection) -> Result<Snapshot, DbErr> {
+    let manager = SchemaManager::new(db);
+    let mut snapshot = Snapshot::default();
+
+    if manager.has_table("resource").await? {
+        // `internal_path` was renamed to `reference` in Memos v0.22.0.
+        let mut path_column = None;
+        for column in ["reference", "internal_path"] {
+            if manager.has_column("resource", column).await? {
+                path_column = Some(column);
+                break;
+            }
+        }
+        if let Some(column) = path_column {
+            let sql = format!("SELECT id, {column} FROM resource;");
+            for row in query_all(db, &sql).await? {
+                let id: i64 = row.try_get_by_index(0)?;
+                let path: Option<String> = row.try_get_by_index(1)?;
+                snapshot.resources.insert(id, path.unwrap_or_default());
+            }
+        }
+    }
+
+    if manager.has_table("system_setting").await?
+        && manager.has_column("system_setting", "name").await?
+        && manager.has_column("system_setting", "value").await?
+    {
+        for row in query_all(db, "SELECT name, value FROM system_setting;").await? {
+            let name: String = row.try_get_by_index(0)?;
+            let value: Option<String> = row.try_get_by_index(1)?;
+            snapshot.settings.insert(name, value.unwrap_or_default());
+        }
+    }
+
+    Ok(snapshot)
+}
+
+async fn query_all(db: &DatabaseConnection, sql: &str) -> Result<Vec<QueryResult>, DbErr> {
+    db.query_all_raw(Statement::from_string(DbBackend::Sqlite, sql))
+        .await
+}
+
+fn diff_resources(before: &Snapshot, after: &Snapshot) -> Vec<ResourceChange> {
+    let ids = before.resources.keys().chain(after.resources.keys());
+    let mut changes: BTreeMap<i64, ResourceChange> = BTreeMap::new();
+    for id in ids {
+        let old = before.resources.get(id);
+        let new = after.resources.get(id);
+        if old != new {
+            changes.entry(*id).or_insert_with(|| ResourceChange {
+                id: *id,
+                before: old.cloned(),
+                after: new.cloned(),
+            });
+        }
+    }
+    changes.into_values().collect()
+}
+
+fn diff_settings(before: &Snapshot, after: &Snapshot) -> Vec<SettingChange> {
+    let names = before.settings.keys().chain(after.settings.keys());
+    let mut changes: BTreeMap<&String, SettingChange> = BTreeMap::new();
+    for name in names {
+        let old = before.settings.get(name);
+        let new = after.settings.get(name);
+        if old != new {
+            changes.entry(name).or_insert_with(|| SettingChange {
+                name: name.clone(),
+                before: old.cloned(),
+                after: new.cloned(),
+            });
+        }
+    }
+    changes.into_values().collect()
+}
@@ -1,5 +1,11 @@
 pub use sea_orm_migration::prelude::*;
 
+pub mod dry_run;
+#[cfg(test)]
+mod tests;
+
+pub use dry_run::{DryRunReport, dry_run};
+
 mod m20220220_000001_migrate_resource_paths;
 mod m20240522_000002_migrate_resource_paths;
 mod m20240525_000001_storage_settings;
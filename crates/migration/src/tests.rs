@@ -0,0 +1,255 @@
+use crate::{Migrator, MigratorTrait, dry_run};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr, Statement};
+use sea_orm_migration::prelude::*;
+use std::path::Path;
+
+/// Memos >=v0.22.0 schema, trimmed to the tables touched by the migrations.
+const FIXTURE_SCHEMA: &str = r#"
+CREATE TABLE resource (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    uid TEXT NOT NULL DEFAULT '',
+    creator_id INTEGER NOT NULL DEFAULT 0,
+    created_ts BIGINT NOT NULL DEFAULT 0,
+    updated_ts BIGINT NOT NULL DEFAULT 0,
+    filename TEXT NOT NULL DEFAULT '',
+    blob BLOB DEFAULT NULL,
+    type TEXT NOT NULL DEFAULT '',
+    size INTEGER NOT NULL DEFAULT 0,
+    memo_id INTEGER,
+    storage_type TEXT NOT NULL DEFAULT '',
+    reference TEXT NOT NULL DEFAULT '',
+    payload TEXT NOT NULL DEFAULT '{}'
+);
+CREATE TABLE system_setting (
+    name TEXT NOT NULL PRIMARY KEY,
+    value TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT ''
+);
+INSERT INTO resource (id, reference) VALUES
+    (1, '/var/opt/memos/assets/202405/1716000000_image.png'),
+    (2, 'assets/202405/1716000001_image.png'),
+    (3, 'https://example.com/image.png');
+INSERT INTO system_setting (name, value) VALUES
+    ('storage-service-id', '-1'),
+    ('local-storage-path', '"assets/{year}/{filename}"'),
+    ('max-upload-size-mib', '64');
+"#;
+
+async fn connect(db_file: &Path) -> DatabaseConnection {
+    Database::connect(format!("sqlite://{}?mode=rwc", db_file.to_string_lossy()))
+        .await
+        .expect("failed to connect to fixture database")
+}
+
+async fn create_fixture(db_file: &Path) {
+    let db = connect(db_file).await;
+    db.execute_unprepared(FIXTURE_SCHEMA)
+        .await
+        .expect("failed to create fixture schema");
+    db.close().await.expect("failed to close fixture database");
+}
+
+async fn setting_names(db: &DatabaseConnection) -> Vec<String> {
+    db.query_all_raw(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT name FROM system_setting ORDER BY name;",
+    ))
+    .await
+    .unwrap()
+    .iter()
+    .map(|row| row.try_get_by_index::<String>(0).unwrap())
+    .collect()
+}
+
+#[tokio::test]
+async fn test_dry_run_reports_changes() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let db_file = tmp_dir.path().join("memos_prod.db");
+    create_fixture(&db_file).await;
+
+    let report = dry_run::<Migrator>(&db_file).await.unwrap();
+
+    let names: Vec<&str> = report.migrations.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "m20220220_000001_migrate_resource_paths",
+            "m20240522_000002_migrate_resource_paths",
+            "m20240525_000001_storage_settings",
+            "m20250221_000001_memospot_v1",
+        ]
+    );
+    assert!(!report.is_empty());
+
+    // Legacy schema migration is skipped for the current schema.
+    assert!(report.migrations[0].is_empty());
+
+    let storage = &report.migrations[2];
+    assert!(storage.resources.is_empty());
+    let created = storage
+        .settings
+        .iter()
+        .find(|s| s.name == "STORAGE")
+        .expect("STORAGE setting should be reported");
+    assert_eq!(created.before, None);
+    let value = created.after.as_deref().unwrap_or_default();
+    assert!(value.contains(r#""storageType":"LOCAL""#));
+    assert!(value.contains(r#""uploadSizeLimitMb":"64""#));
+    for removed in [
+        "local-storage-path",
+        "max-upload-size-mib",
+        "storage-service-id",
+    ] {
+        let change = storage
+            .settings
+            .iter()
+            .find(|s| s.name == removed)
+            .unwrap_or_else(|| panic!("{removed} removal should be reported"));
+        assert!(change.before.is_some());
+        assert_eq!(change.after, None);
+    }
+
+    assert!(report.migrations[3].is_empty());
+}
+
+/// Strips the `/var/opt/memos/` prefix from resource paths.
+mod strip_prefix {
+    use super::*;
+
+    #[derive(DeriveMigrationName)]
+    pub struct Migration;
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for Migration {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "UPDATE resource SET reference = substr(reference, 16) WHERE reference LIKE '/var/opt/memos/%';",
+                )
+                .await?;
+            Ok(())
+        }
+    }
+}
+
+struct TestMigrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for TestMigrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![Box::new(strip_prefix::Migration)]
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_reports_resource_changes() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let db_file = tmp_dir.path().join("memos_prod.db");
+    create_fixture(&db_file).await;
+
+    let report = dry_run::<TestMigrator>(&db_file).await.unwrap();
+
+    assert_eq!(report.len(), 1);
+    let migration = &report.migrations[0];
+    assert!(migration.settings.is_empty());
+    assert_eq!(migration.resources.len(), 1);
+    let change = &migration.resources[0];
+    assert_eq!(change.id, 1);
+    assert_eq!(
+        change.before.as_deref(),
+        Some("/var/opt/memos/assets/202405/1716000000_image.png")
+    );
+    assert_eq!(
+        change.after.as_deref(),
+        Some("assets/202405/1716000000_image.png")
+    );
+}
+
+/// Deletes the remote resource and inserts a new one.
+mod replace_remote {
+    use super::*;
+
+    #[derive(DeriveMigrationName)]
+    pub struct Migration;
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for Migration {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "DELETE FROM resource WHERE id = 3; INSERT INTO resource (id, reference) VALUES (4, 'assets/image.png');",
+                )
+                .await?;
+            Ok(())
+        }
+    }
+}
+
+struct ReplaceMigrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for ReplaceMigrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![Box::new(replace_remote::Migration)]
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_reports_inserted_and_deleted_resources() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let db_file = tmp_dir.path().join("memos_prod.db");
+    create_fixture(&db_file).await;
+
+    let report = dry_run::<ReplaceMigrator>(&db_file).await.unwrap();
+
+    assert_eq!(report.len(), 2);
+    let resources = &report.migrations[0].resources;
+    assert_eq!(resources[0].id, 3);
+    assert_eq!(
+        resources[0].before.as_deref(),
+        Some("https://example.com/image.png")
+    );
+    assert_eq!(resources[0].after, None);
+    assert_eq!(resources[1].id, 4);
+    assert_eq!(resources[1].before, None);
+    assert_eq!(resources[1].after.as_deref(), Some("assets/image.png"));
+}
+
+#[tokio::test]
+async fn test_dry_run_leaves_source_untouched() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let db_file = tmp_dir.path().join("memos_prod.db");
+    create_fixture(&db_file).await;
+
+    dry_run::<Migrator>(&db_file).await.unwrap();
+
+    let db = connect(&db_file).await;
+    assert_eq!(
+        setting_names(&db).await,
+        [
+            "local-storage-path",
+            "max-upload-size-mib",
+            "storage-service-id",
+        ]
+    );
+    let pending = Migrator::get_pending_migrations_read_only(&db)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), Migrator::migrations().len());
+    db.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_dry_run_unsupported_schema() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let db_file = tmp_dir.path().join("memos_prod.db");
+    connect(&db_file).await.close().await.unwrap();
+
+    let report = dry_run::<Migrator>(&db_file).await.unwrap();
+
+    assert_eq!(report.migrations.len(), Migrator::migrations().len());
+    assert!(report.is_empty());
+    assert_eq!(report.len(), 0);
+}
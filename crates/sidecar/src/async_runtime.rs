@@ -1,7 +1,7 @@
 use std::future::Future;
 use std::sync::OnceLock;
 pub use tokio::{
-    runtime::Runtime,
+    runtime::{Handle, Runtime},
     sync::mpsc::{Receiver, Sender, channel},
 };
 
@@ -19,3 +19,11 @@ pub fn block_on<F: Future>(task: F) -> F::Output {
     let rt = RUNTIME.get_or_init(default_runtime);
     rt.block_on(task)
 }
+
+/// Returns a handle to the current Tokio runtime.
+///
+/// Outside of a runtime context, falls back to the default runtime used by [`block_on`].
+pub fn handle() -> Handle {
+    Handle::try_current()
+        .unwrap_or_else(|_| RUNTIME.get_or_init(default_runtime).handle().clone())
+}
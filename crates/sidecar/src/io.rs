@@ -0,0 +1,46 @@
+//! Async IO helpers.
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// Read all bytes until a newline (the `0xA` byte) or a carriage return (`\r`) is reached, and append them to the provided buffer.
+///
+/// Stops early once `buf` holds `limit` bytes.
+///
+/// Async counterpart of [`tauri_utils::io::read_line`].
+pub async fn read_line<R: AsyncBufRead + Unpin + ?Sized>(
+    r: &mut R,
+    buf: &mut Vec<u8>,
+    limit: usize,
+) -> std::io::Result<usize> {
+    let mut read = 0;
+    loop {
+        let (done, used) = {
+            let available = match r.fill_buf().await {
+                Ok(n) => n,
+                Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            let available = &available[..available.len().min(limit.saturating_sub(buf.len()))];
+            let delimiter = available
+                .iter()
+                .position(|&b| b == b'\n')
+                .or_else(|| available.iter().position(|&b| b == b'\r'));
+            match delimiter {
+                Some(i) => {
+                    let end = i + 1;
+                    buf.extend_from_slice(&available[..end]);
+                    (true, end)
+                }
+                None => {
+                    buf.extend_from_slice(available);
+                    (false, available.len())
+                }
+            }
+        };
+        r.consume(used);
+        read += used;
+        if done || used == 0 || buf.len() >= limit {
+            return Ok(read);
+        }
+    }
+}
@@ -5,40 +5,60 @@
 //!
 //! Modified to send CTRL+BREAK to the console process group on Windows and to send SIGINT on UNIX systems.
 //!
+//! Built on Tokio's process API: pipes are read by async tasks on the current runtime
+//! (or on a default runtime, outside of one), so no OS thread is dedicated to a child.
+//! Events are delivered through a bounded channel, see [`Command::buffer_size`].
+//!
 
 mod async_runtime;
+mod io;
+mod limits;
+mod metrics;
+mod output;
+mod shutdown;
 mod tests;
 
 use anyhow::{Result, format_err};
 pub use async_runtime::{Receiver, Sender};
 use async_runtime::{block_on, channel};
 use encoding_rs::Encoding;
+pub use limits::ResourceLimits;
 use log::debug;
-use os_pipe::{PipeReader, PipeWriter, pipe};
+pub use metrics::EventMetrics;
+use metrics::Metrics;
+use output::LineDecoder;
+pub use output::OutputMode;
 use serde::Serialize;
-use shared_child::SharedChild;
+pub use shutdown::{ShutdownPolicy, ShutdownSignal, ShutdownStep, Termination};
 use std::{
     collections::HashMap,
-    io::{BufReader, Write},
     path::PathBuf,
     process::{Command as StdCommand, Stdio},
-    sync::{Arc, Mutex, RwLock},
-    thread::spawn,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 use tauri_utils::platform;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader},
+    process::{ChildStdin, Command as TokioCommand},
+    sync::{oneshot, watch},
+};
 
-#[cfg(unix)]
-use shared_child::unix::SharedChildExt;
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 
+#[cfg(unix)]
+mod unix;
 #[cfg(windows)]
 mod windows;
 
 #[cfg(windows)]
 use crate::windows::CommandCreationFlagsExt;
 
-type ChildStore = Arc<Mutex<HashMap<u32, Arc<SharedChild>>>>;
+/// Default capacity of the event channel, in events.
+pub const DEFAULT_BUFFER_SIZE: usize = 1024;
+
+type ChildStore = Arc<Mutex<HashMap<u32, Arc<ChildHandle>>>>;
 
 fn commands() -> &'static ChildStore {
     use std::sync::LazyLock;
@@ -46,58 +66,160 @@ fn commands() -> &'static ChildStore {
     &STORE
 }
 
-/// Kills all child processes created with [`Command`].
-///
-/// Tries to send a SIGINT on UNIX systems.
-/// On Windows, attempts to send CTRL+BREAK to the console process group.
+/// State shared between a spawned child, its waiter task and [`terminate_children`].
 ///
-/// By default, it's called before the [`crate::App`] exits.
-pub fn kill_children() {
-    const TIMEOUT_MS: u128 = 1_500;
+/// The [`tokio::process::Child`] itself is owned by the waiter task, which reaps it.
+#[derive(Debug)]
+struct ChildHandle {
+    pid: u32,
+    policy: ShutdownPolicy,
+    /// Whether the child leads its own process group, which signals are sent to.
+    process_group: bool,
+    /// Asks the waiter task to kill the child.
+    kill_tx: Mutex<Option<oneshot::Sender<()>>>,
+    /// Set by the waiter task once the child has been reaped.
+    status: watch::Sender<Option<TerminatedPayload>>,
+}
 
-    let commands = commands().lock().expect("unable to acquire lock");
-    let children = commands.values();
-    for child in children {
-        let pid = child.id();
-        debug!("sidecar: terminating pid {}", pid);
+impl ChildHandle {
+    fn has_exited(&self) -> bool {
+        self.status.borrow().is_some()
+    }
 
-        #[cfg(unix)]
-        {
-            const SIGINT: i32 = 2;
-            child.send_signal(SIGINT).ok();
+    /// Asks the waiter task to kill the child.
+    fn kill(&self) -> Result<()> {
+        let kill_tx = self.kill_tx.lock().expect("unable to acquire lock").take();
+        match kill_tx {
+            Some(tx) => {
+                tx.send(())
+                    .map_err(|_| format_err!("process {} already exited", self.pid))?;
+                Ok(())
+            }
+            None => Err(format_err!("process {} is already being killed", self.pid)),
+        }
+    }
+
+    fn send_signal(&self, signal: ShutdownSignal) {
+        // The pid may have been reused once the child was reaped.
+        if self.has_exited() {
+            return;
+        }
+        if signal == ShutdownSignal::Kill {
+            // Take down the whole group, then let the waiter task kill and reap the child.
+            #[cfg(unix)]
+            if self.process_group {
+                unix::send_signal(self.pid, true, signal);
+            }
+            self.kill().ok();
+            return;
         }
 
+        #[cfg(unix)]
+        unix::send_signal(self.pid, self.process_group, signal);
+
+        // There's no SIGTERM on Windows: both signals break the console process group.
         #[cfg(windows)]
-        windows::send_ctrl_break(pid);
-
-        let time_start = std::time::Instant::now();
-        let mut timed_out = false;
-        while child.try_wait().is_ok_and(|x| x.is_none()) {
-            if time_start.elapsed().as_millis() > TIMEOUT_MS {
-                timed_out = true;
-                break;
-            };
-            std::thread::sleep(std::time::Duration::from_millis(100));
+        windows::send_ctrl_break(self.pid);
+    }
+
+    /// Walks the shutdown policy until the child exits.
+    async fn terminate(&self) -> Termination {
+        let mut status = self.status.subscribe();
+        if let Some(payload) = status.borrow_and_update().clone() {
+            return Termination::AlreadyExited(payload);
         }
-        if timed_out {
-            debug!(
-                "sidecar: timed out ({} ms) waiting for pid {} to exit",
-                TIMEOUT_MS, pid
-            );
-        } else {
-            debug!(
-                "sidecar: pid {} exited gracefully in <{:?}ms",
-                pid,
-                time_start.elapsed().as_millis()
-            );
+
+        let time_start = Instant::now();
+        for step in self.policy.steps() {
+            debug!("sidecar: sending {:?} to pid {}", step.signal, self.pid);
+            self.send_signal(step.signal);
+
+            let exited = tokio::time::timeout(step.timeout, status.wait_for(Option::is_some));
+            if let Ok(Ok(payload)) = exited.await
+                && let Some(payload) = payload.clone()
+            {
+                let elapsed = time_start.elapsed();
+                debug!(
+                    "sidecar: pid {} exited after {:?} in <{:?}ms",
+                    self.pid,
+                    step.signal,
+                    elapsed.as_millis()
+                );
+                return Termination::Exited {
+                    signal: step.signal,
+                    status: payload,
+                    elapsed,
+                };
+            }
         }
 
-        child.kill().ok();
+        let elapsed = time_start.elapsed();
+        debug!(
+            "sidecar: timed out ({} ms) waiting for pid {} to exit",
+            elapsed.as_millis(),
+            self.pid
+        );
+        Termination::TimedOut { elapsed }
+    }
+}
+
+/// Terminates all child processes created with [`Command`], each following its [`ShutdownPolicy`].
+///
+/// Children are terminated concurrently. Returns how each of them exited, by pid.
+pub async fn terminate_children() -> Vec<(u32, Termination)> {
+    // Don't hold the lock while waiting: waiter tasks remove their child from the store.
+    let children: Vec<Arc<ChildHandle>> = commands()
+        .lock()
+        .expect("unable to acquire lock")
+        .values()
+        .cloned()
+        .collect();
+
+    let tasks: Vec<_> = children
+        .into_iter()
+        .map(|child| {
+            debug!("sidecar: terminating pid {}", child.pid);
+            tokio::spawn(async move { (child.pid, child.terminate().await) })
+        })
+        .collect();
+
+    let mut terminations = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        if let Ok(termination) = task.await {
+            terminations.push(termination);
+        }
+    }
+    terminations
+}
+
+/// Kills all child processes created with [`Command`].
+///
+/// Blocking version of [`terminate_children`]: tries to send a SIGINT on UNIX systems and,
+/// on Windows, attempts to send CTRL+BREAK to the console process group, escalating
+/// as set by each child's [`ShutdownPolicy`].
+///
+/// By default, it's called before the [`crate::App`] exits.
+pub fn kill_children() {
+    let timeout = commands()
+        .lock()
+        .expect("unable to acquire lock")
+        .values()
+        .map(|child| child.policy.total_timeout())
+        .max()
+        .unwrap_or_default();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    async_runtime::handle().spawn(async move {
+        tx.send(terminate_children().await).ok();
+    });
+    // Bounded, in case the calling thread is the one driving the runtime.
+    if rx.recv_timeout(timeout + Duration::from_secs(1)).is_err() {
+        debug!("sidecar: timed out waiting for children to terminate");
     }
 }
 
 /// Payload for the [`CommandEvent::Terminated`] command event.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct TerminatedPayload {
     /// Exit code of the process.
     pub code: Option<i32>,
@@ -111,9 +233,19 @@ pub struct TerminatedPayload {
 #[non_exhaustive]
 pub enum CommandEvent {
     /// Stderr bytes until a newline (\n) or carriage return (\r) is found.
+    ///
+    /// Lines longer than [`Command::max_line_length`] are split, and only the last piece
+    /// ends with the delimiter.
     Stderr(String),
     /// Stdout bytes until a newline (\n) or carriage return (\r) is found.
+    ///
+    /// Lines longer than [`Command::max_line_length`] are split, and only the last piece
+    /// ends with the delimiter.
     Stdout(String),
+    /// Raw stderr bytes, in [`OutputMode::Bytes`].
+    StderrBytes(Vec<u8>),
+    /// Raw stdout bytes, in [`OutputMode::Bytes`].
+    StdoutBytes(Vec<u8>),
     /// An error happened waiting for the command to finish or converting the stdout/stderr bytes to a UTF-8 string.
     Error(String),
     /// Command process terminated.
@@ -129,31 +261,57 @@ pub struct Command {
     env: HashMap<String, String>,
     current_dir: Option<PathBuf>,
     encoding: Option<&'static Encoding>,
+    output_mode: OutputMode,
+    max_line_length: Option<usize>,
+    buffer_size: usize,
+    shutdown_policy: ShutdownPolicy,
+    process_group: bool,
+    kill_on_parent_death: bool,
+    resource_limits: ResourceLimits,
 }
 
 /// Spawned child process.
 #[derive(Debug)]
 pub struct CommandChild {
-    inner: Arc<SharedChild>,
-    stdin_writer: PipeWriter,
+    handle: Arc<ChildHandle>,
+    stdin: ChildStdin,
+    metrics: Arc<Metrics>,
 }
 
 impl CommandChild {
     /// Writes to process stdin.
-    pub fn write(&mut self, buf: &[u8]) -> Result<()> {
-        self.stdin_writer.write_all(buf)?;
+    pub async fn write(&mut self, buf: &[u8]) -> Result<()> {
+        self.stdin.write_all(buf).await?;
+        self.stdin.flush().await?;
         Ok(())
     }
 
     /// Sends a kill signal to the child.
+    ///
+    /// The child is killed and reaped asynchronously. A [`CommandEvent::Terminated`]
+    /// event follows once it exits.
     pub fn kill(self) -> Result<()> {
-        self.inner.kill()?;
-        Ok(())
+        self.handle.kill()
+    }
+
+    /// Shuts the child down following its [`ShutdownPolicy`], and returns how it exited.
+    pub async fn terminate(&self) -> Termination {
+        self.handle.terminate().await
+    }
+
+    /// Returns the shutdown policy used by [`CommandChild::terminate`].
+    pub fn shutdown_policy(&self) -> &ShutdownPolicy {
+        &self.handle.policy
     }
 
     /// Returns the process pid.
     pub fn pid(&self) -> u32 {
-        self.inner.id()
+        self.handle.pid
+    }
+
+    /// Returns the event channel statistics.
+    pub fn metrics(&self) -> EventMetrics {
+        self.metrics.snapshot()
     }
 }
 
@@ -214,6 +372,27 @@ impl From<Command> for StdCommand {
         if let Some(current_dir) = cmd.current_dir {
             command.current_dir(current_dir);
         }
+        #[cfg(unix)]
+        if cmd.process_group {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        #[cfg(target_os = "linux")]
+        if cmd.kill_on_parent_death {
+            use std::os::unix::process::CommandExt;
+            // SAFETY: the hook only makes async-signal-safe system calls.
+            unsafe {
+                command.pre_exec(unix::parent_death_signal());
+            }
+        }
+        #[cfg(target_os = "linux")]
+        if !cmd.resource_limits.is_empty() {
+            use std::os::unix::process::CommandExt;
+            // SAFETY: the hook only makes async-signal-safe system calls.
+            unsafe {
+                command.pre_exec(unix::resource_limits(cmd.resource_limits));
+            }
+        }
         #[cfg(windows)]
         command.apply_creation_flags();
         command
@@ -230,6 +409,13 @@ impl Command {
             env: Default::default(),
             current_dir: None,
             encoding: None,
+            output_mode: OutputMode::default(),
+            max_line_length: None,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            shutdown_policy: ShutdownPolicy::default(),
+            process_group: false,
+            kill_on_parent_death: false,
+            resource_limits: ResourceLimits::default(),
         }
     }
 
@@ -282,8 +468,78 @@ impl Command {
         self
     }
 
+    /// Sets how stdout/stderr are turned into events. Defaults to [`OutputMode::Lines`].
+    #[must_use]
+    pub fn output_mode(mut self, mode: OutputMode) -> Self {
+        self.output_mode = mode;
+        self
+    }
+
+    /// Caps the length of a line, in bytes. Longer lines are split into several events,
+    /// instead of being buffered whole. Unlimited by default.
+    ///
+    /// In [`OutputMode::Bytes`], caps the size of each chunk.
+    #[must_use]
+    pub fn max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length.replace(max_line_length.max(1));
+        self
+    }
+
+    /// Sets the capacity of the event channel, in events. Defaults to [`DEFAULT_BUFFER_SIZE`].
+    ///
+    /// Once the channel is full, the pipes are no longer read until the receiver catches up,
+    /// and the child eventually blocks on its own output. See [`CommandChild::metrics`].
+    #[must_use]
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size.max(1);
+        self
+    }
+
+    /// Sets how the child is shut down by [`CommandChild::terminate`] and [`kill_children`].
+    #[must_use]
+    pub fn shutdown_policy(mut self, policy: ShutdownPolicy) -> Self {
+        self.shutdown_policy = policy;
+        self
+    }
+
+    /// Places the child in its own process group (UNIX only).
+    ///
+    /// Shutdown signals are then sent to the whole group, so processes spawned by the child
+    /// are terminated along with it. Terminal signals, like Ctrl+C, no longer reach the child.
+    ///
+    /// On Windows, children always get their own console process group.
+    #[must_use]
+    pub fn process_group(mut self, process_group: bool) -> Self {
+        self.process_group = process_group;
+        self
+    }
+
+    /// Kills the child if this process dies, even if it crashes (Linux only).
+    ///
+    /// Sets `PR_SET_PDEATHSIG` to SIGKILL in the child. The signal fires when the thread
+    /// that spawned the child exits, so spawn from a long-lived thread, like a Tokio
+    /// runtime worker or the main thread. Ignored on other platforms.
+    #[must_use]
+    pub fn kill_on_parent_death(mut self, kill_on_parent_death: bool) -> Self {
+        self.kill_on_parent_death = kill_on_parent_death;
+        self
+    }
+
+    /// Sets resource limits and privileges for the child (Linux only).
+    ///
+    /// Spawning fails if a limit can't be applied, like a niceness the current user isn't
+    /// allowed to set. Ignored on other platforms.
+    #[must_use]
+    pub fn resource_limits(mut self, limits: ResourceLimits) -> Self {
+        self.resource_limits = limits;
+        self
+    }
+
     /// Spawns the command.
     ///
+    /// Pipe readers and the process waiter run as tasks on the current Tokio runtime.
+    /// Outside of a runtime, a default multi-threaded runtime is used.
+    ///
     /// # Examples
     ///
     /// ```rust,no_run
@@ -300,7 +556,7 @@ impl Command {
     ///       println!("got: {}", line);
     ///       i += 1;
     ///       if i == 4 {
-    ///         child.write("message from Rust\n".as_bytes()).unwrap();
+    ///         child.write("message from Rust\n".as_bytes()).await.unwrap();
     ///         i = 0;
     ///       }
     ///     }
@@ -308,73 +564,103 @@ impl Command {
     /// });
     /// ```
     pub fn spawn(self) -> Result<(Receiver<CommandEvent>, CommandChild)> {
-        let encoding = self.encoding;
-        let mut command: StdCommand = self.into();
-        let (stdout_reader, stdout_writer) = pipe()?;
-        let (stderr_reader, stderr_writer) = pipe()?;
-        let (stdin_reader, stdin_writer) = pipe()?;
-        command.stdout(stdout_writer);
-        command.stderr(stderr_writer);
-        command.stdin(stdin_reader);
-
-        let shared_child = SharedChild::spawn(&mut command)?;
-        let child = Arc::new(shared_child);
-        let child_ = child.clone();
-        let guard = Arc::new(RwLock::new(()));
-
-        let pid = child.id();
+        let runtime = async_runtime::handle();
+        // Child processes must be spawned within a runtime context to be reaped by it.
+        let _guard = runtime.enter();
+
+        let reader = PipeReader {
+            mode: self.output_mode,
+            encoding: self.encoding,
+            max_line_length: self.max_line_length.unwrap_or(usize::MAX),
+        };
+        let policy = self.shutdown_policy.clone();
+        let process_group = cfg!(unix) && self.process_group;
+        #[cfg(target_os = "linux")]
+        let nice = self.resource_limits.nice;
+        let (tx, rx) = channel(self.buffer_size);
+        let mut command = TokioCommand::from(StdCommand::from(self));
+        let mut child = command.spawn()?;
+
+        let pid = child
+            .id()
+            .ok_or_else(|| format_err!("child process exited before it could be tracked"))?;
+        #[cfg(target_os = "linux")]
+        if let Some(nice) = nice {
+            unix::warn_unapplied_priority(pid, nice);
+        }
+        let (Some(stdout), Some(stderr), Some(stdin)) =
+            (child.stdout.take(), child.stderr.take(), child.stdin.take())
+        else {
+            return Err(format_err!("failed to capture stdio for pid {pid}"));
+        };
         debug!("sidecar: spawned child process with pid: {} ", pid);
 
+        let (kill_tx, kill_rx) = oneshot::channel();
+        let handle = Arc::new(ChildHandle {
+            pid,
+            policy,
+            process_group,
+            kill_tx: Mutex::new(Some(kill_tx)),
+            status: watch::Sender::new(None),
+        });
         commands()
             .lock()
             .expect("unable to acquire lock")
-            .insert(pid, child.clone());
-
-        let (tx, rx) = channel(1);
-
-        spawn_pipe_reader(
-            tx.clone(),
-            guard.clone(),
-            stdout_reader,
-            CommandEvent::Stdout,
-            encoding,
-        );
-        spawn_pipe_reader(
-            tx.clone(),
-            guard.clone(),
-            stderr_reader,
-            CommandEvent::Stderr,
-            encoding,
-        );
-
-        spawn(move || {
-            let _ = match child_.wait() {
-                Ok(status) => {
-                    let _l = guard.write().unwrap();
-                    commands().lock().unwrap().remove(&child_.id());
-                    block_on(async move {
-                        tx.send(CommandEvent::Terminated(TerminatedPayload {
-                            code: status.code(),
-                            #[cfg(windows)]
-                            signal: None,
-                            #[cfg(unix)]
-                            signal: status.signal(),
-                        }))
-                        .await
-                    })
-                }
-                Err(e) => {
-                    let _l = guard.write().unwrap();
-                    block_on(async move { tx.send(CommandEvent::Error(e.to_string())).await })
+            .insert(pid, handle.clone());
+
+        let metrics = Arc::new(Metrics::default());
+        let stdout_reader =
+            runtime.spawn(reader.read(stdout, Pipe::Stdout, tx.clone(), metrics.clone()));
+        let stderr_reader =
+            runtime.spawn(reader.read(stderr, Pipe::Stderr, tx.clone(), metrics.clone()));
+
+        let handle_ = handle.clone();
+        let metrics_ = metrics.clone();
+        runtime.spawn(async move {
+            let status = tokio::select! {
+                status = child.wait() => status,
+                Ok(()) = kill_rx => {
+                    debug!("sidecar: killing pid {}", pid);
+                    child.start_kill().ok();
+                    child.wait().await
                 }
             };
+            let payload = match &status {
+                Ok(status) => TerminatedPayload {
+                    code: status.code(),
+                    #[cfg(windows)]
+                    signal: None,
+                    #[cfg(unix)]
+                    signal: status.signal(),
+                },
+                Err(_) => TerminatedPayload {
+                    code: None,
+                    signal: None,
+                },
+            };
+            handle_.status.send_replace(Some(payload.clone()));
+            commands()
+                .lock()
+                .expect("unable to acquire lock")
+                .remove(&pid);
+
+            // Deliver the remaining output before the termination event.
+            stdout_reader.await.ok();
+            stderr_reader.await.ok();
+
+            let event = match status {
+                Ok(_) => CommandEvent::Terminated(payload),
+                Err(e) => CommandEvent::Error(e.to_string()),
+            };
+            metrics_.send(&tx, event).await;
         });
 
         Ok((
             rx,
             CommandChild {
-                inner: child,
-                stdin_writer,
+                handle,
+                stdin,
+                metrics,
             },
         ))
     }
@@ -434,6 +720,12 @@ impl Command {
                         stderr.push_str(line.as_str());
                         stderr.push('\n');
                     }
+                    CommandEvent::StdoutBytes(bytes) => {
+                        stdout.push_str(&String::from_utf8_lossy(&bytes));
+                    }
+                    CommandEvent::StderrBytes(bytes) => {
+                        stderr.push_str(&String::from_utf8_lossy(&bytes));
+                    }
                     CommandEvent::Error(_) => {}
                 }
             }
@@ -448,46 +740,93 @@ impl Command {
     }
 }
 
-fn spawn_pipe_reader<F: Fn(String) -> CommandEvent + Send + Copy + 'static>(
-    tx: Sender<CommandEvent>,
-    guard: Arc<RwLock<()>>,
-    pipe_reader: PipeReader,
-    wrapper: F,
-    character_encoding: Option<&'static Encoding>,
-) {
-    spawn(move || {
-        let _lock = guard.read().unwrap();
-        let mut reader = BufReader::new(pipe_reader);
+#[derive(Debug, Clone, Copy)]
+enum Pipe {
+    Stdout,
+    Stderr,
+}
+
+impl Pipe {
+    fn text(self, line: String) -> CommandEvent {
+        match self {
+            Pipe::Stdout => CommandEvent::Stdout(line),
+            Pipe::Stderr => CommandEvent::Stderr(line),
+        }
+    }
 
+    fn bytes(self, bytes: Vec<u8>) -> CommandEvent {
+        match self {
+            Pipe::Stdout => CommandEvent::StdoutBytes(bytes),
+            Pipe::Stderr => CommandEvent::StderrBytes(bytes),
+        }
+    }
+}
+
+/// Turns a child pipe into events.
+#[derive(Debug, Clone, Copy)]
+struct PipeReader {
+    mode: OutputMode,
+    encoding: Option<&'static Encoding>,
+    max_line_length: usize,
+}
+
+impl PipeReader {
+    /// Reads `pipe` to the end.
+    ///
+    /// Keeps draining the pipe even if the receiver is gone,
+    /// so the child never blocks on a full pipe.
+    async fn read<R>(self, pipe: R, kind: Pipe, tx: Sender<CommandEvent>, metrics: Arc<Metrics>)
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut reader = BufReader::new(pipe);
+        if self.mode == OutputMode::Bytes {
+            let mut chunk = vec![0; self.max_line_length.min(8 * 1024)];
+            loop {
+                match reader.read(&mut chunk).await {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        metrics.send(&tx, kind.bytes(chunk[..n].to_vec())).await;
+                    }
+                    Err(e) => {
+                        metrics.send(&tx, CommandEvent::Error(e.to_string())).await;
+                        break;
+                    }
+                }
+            }
+            return;
+        }
+
+        let mut decoder = LineDecoder::new(self.encoding, self.mode == OutputMode::LossyLines);
         let mut buf = Vec::new();
         loop {
             buf.clear();
-            match tauri_utils::io::read_line(&mut reader, &mut buf) {
-                Ok(n) => {
-                    if n == 0 {
-                        break;
-                    }
-                    let tx_ = tx.clone();
-                    let line = match character_encoding {
-                        Some(encoding) => Ok(encoding.decode_with_bom_removal(&buf).0.into()),
-                        None => String::from_utf8(buf.clone()),
+            match io::read_line(&mut reader, &mut buf, self.max_line_length).await {
+                Ok(0) => {
+                    // Flush a multi-byte sequence cut short by the end of the stream.
+                    let event = match decoder.decode(&[], true) {
+                        Ok(rest) if rest.is_empty() => break,
+                        Ok(rest) => kind.text(rest),
+                        Err(e) => CommandEvent::Error(e),
+                    };
+                    metrics.send(&tx, event).await;
+                    break;
+                }
+                Ok(_) => {
+                    let last = buf.ends_with(b"\n") || buf.ends_with(b"\r");
+                    let event = match decoder.decode(&buf, last) {
+                        // An incomplete sequence at the end of a split line.
+                        Ok(line) if line.is_empty() => continue,
+                        Ok(line) => kind.text(line),
+                        Err(e) => CommandEvent::Error(e),
                     };
-                    block_on(async move {
-                        let _ = match line {
-                            Ok(line) => tx_.send(wrapper(line)).await,
-                            Err(e) => tx_.send(CommandEvent::Error(e.to_string())).await,
-                        };
-                    });
+                    metrics.send(&tx, event).await;
                 }
                 Err(e) => {
-                    let tx_ = tx.clone();
-                    let _ =
-                        block_on(
-                            async move { tx_.send(CommandEvent::Error(e.to_string())).await },
-                        );
+                    metrics.send(&tx, CommandEvent::Error(e.to_string())).await;
                     break;
                 }
             }
         }
-    });
+    }
 }
@@ -0,0 +1,29 @@
+//! Resource limits.
+
+/// Resource limits and privileges of a spawned child (Linux only).
+///
+/// Limits can only be lowered: values above the current hard limit are clamped to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Maximum size of the virtual address space, in bytes (`RLIMIT_AS`).
+    pub address_space: Option<u64>,
+    /// Maximum number of open file descriptors (`RLIMIT_NOFILE`).
+    pub open_files: Option<u64>,
+    /// Maximum CPU time, in seconds (`RLIMIT_CPU`). The child is killed once exceeded.
+    pub cpu_time: Option<u64>,
+    /// Scheduling priority, from -20 (highest) to 19 (lowest).
+    ///
+    /// Raising the priority above the current one requires privileges. Without them, the
+    /// current priority is kept and a warning is logged, instead of failing the spawn.
+    pub nice: Option<i32>,
+    /// Sets `PR_SET_NO_NEW_PRIVS`, so the child and its descendants can't gain privileges
+    /// through setuid binaries or file capabilities.
+    pub no_new_privileges: bool,
+}
+
+impl ResourceLimits {
+    /// Whether no limit is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
@@ -0,0 +1,75 @@
+//! Event channel statistics.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::Sender;
+use tokio::sync::mpsc::error::TrySendError;
+
+/// Snapshot of the event channel statistics of a spawned child.
+///
+/// A non-zero `backpressure_waits` means the receiver couldn't keep up with the child output
+/// and the pipe readers had to wait. Consider a larger [`crate::Command::buffer_size`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EventMetrics {
+    /// Events delivered to the channel.
+    pub sent: u64,
+    /// Events discarded because the receiver was dropped.
+    pub dropped: u64,
+    /// Sends that found the channel full and had to wait for the receiver.
+    pub backpressure_waits: u64,
+    /// Total time spent waiting on a full channel.
+    pub backpressure_time: Duration,
+    /// Highest number of events queued at once.
+    pub peak_queued: usize,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Metrics {
+    sent: AtomicU64,
+    dropped: AtomicU64,
+    backpressure_waits: AtomicU64,
+    backpressure_nanos: AtomicU64,
+    peak_queued: AtomicUsize,
+}
+
+impl Metrics {
+    pub(crate) fn snapshot(&self) -> EventMetrics {
+        EventMetrics {
+            sent: self.sent.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            backpressure_waits: self.backpressure_waits.load(Ordering::Relaxed),
+            backpressure_time: Duration::from_nanos(
+                self.backpressure_nanos.load(Ordering::Relaxed),
+            ),
+            peak_queued: self.peak_queued.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Send `event`, waiting for room if the channel is full.
+    ///
+    /// Returns `false` if the receiver was dropped.
+    pub(crate) async fn send<T>(&self, tx: &Sender<T>, event: T) -> bool {
+        let sent = match tx.try_send(event) {
+            Ok(()) => true,
+            Err(TrySendError::Full(event)) => {
+                let started = Instant::now();
+                let sent = tx.send(event).await.is_ok();
+                let waited = started.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+                self.backpressure_waits.fetch_add(1, Ordering::Relaxed);
+                self.backpressure_nanos.fetch_add(waited, Ordering::Relaxed);
+                sent
+            }
+            Err(TrySendError::Closed(_)) => false,
+        };
+
+        if sent {
+            self.sent.fetch_add(1, Ordering::Relaxed);
+            let queued = tx.max_capacity() - tx.capacity();
+            self.peak_queued.fetch_max(queued, Ordering::Relaxed);
+        } else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        sent
+    }
+}
@@ -0,0 +1,81 @@
+//! Child output decoding.
+
+use encoding_rs::{CoderResult, Decoder, DecoderResult, Encoding, UTF_8};
+
+/// How the child stdout/stderr are turned into events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Lines, as [`crate::CommandEvent::Stdout`] and [`crate::CommandEvent::Stderr`].
+    ///
+    /// Lines that aren't valid UTF-8 are dropped and reported as [`crate::CommandEvent::Error`],
+    /// unless an [`crate::Command::encoding`] is set.
+    #[default]
+    Lines,
+    /// Lines, with invalid sequences replaced by `U+FFFD`. Nothing is dropped.
+    LossyLines,
+    /// Raw byte chunks as read from the pipes, as [`crate::CommandEvent::StdoutBytes`]
+    /// and [`crate::CommandEvent::StderrBytes`].
+    Bytes,
+}
+
+/// Incremental line decoder.
+///
+/// Keeps the decoder state across the pieces of a line split by the length cap, so
+/// multi-byte sequences that straddle two pieces are decoded as one character.
+pub(crate) struct LineDecoder {
+    encoding: Option<&'static Encoding>,
+    lossy: bool,
+    decoder: Decoder,
+}
+
+impl LineDecoder {
+    pub fn new(encoding: Option<&'static Encoding>, lossy: bool) -> Self {
+        Self {
+            encoding,
+            lossy,
+            decoder: Self::new_decoder(encoding),
+        }
+    }
+
+    fn new_decoder(encoding: Option<&'static Encoding>) -> Decoder {
+        match encoding {
+            Some(encoding) => encoding.new_decoder_with_bom_removal(),
+            None => UTF_8.new_decoder_without_bom_handling(),
+        }
+    }
+
+    /// Decodes a piece of a line. `last` marks the end of the line, and resets the decoder.
+    ///
+    /// Fails on invalid UTF-8, unless decoding is lossy or an encoding is set.
+    pub fn decode(&mut self, bytes: &[u8], last: bool) -> Result<String, String> {
+        let capacity = self
+            .decoder
+            .max_utf8_buffer_length(bytes.len())
+            .unwrap_or(bytes.len());
+        let mut line = String::with_capacity(capacity);
+
+        let result = if self.lossy || self.encoding.is_some() {
+            let (result, _, _) = self.decoder.decode_to_string(bytes, &mut line, last);
+            match result {
+                CoderResult::InputEmpty => Ok(line),
+                CoderResult::OutputFull => Err("output buffer too small".to_string()),
+            }
+        } else {
+            let (result, _) = self
+                .decoder
+                .decode_to_string_without_replacement(bytes, &mut line, last);
+            match result {
+                DecoderResult::InputEmpty => Ok(line),
+                DecoderResult::Malformed(..) => Err(format!(
+                    "invalid utf-8 sequence: {}",
+                    String::from_utf8_lossy(bytes)
+                )),
+                DecoderResult::OutputFull => Err("output buffer too small".to_string()),
+            }
+        };
+        if last || result.is_err() {
+            self.decoder = Self::new_decoder(self.encoding);
+        }
+        result
+    }
+}
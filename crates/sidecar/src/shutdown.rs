@@ -0,0 +1,114 @@
+//! Graceful shutdown policy.
+
+use crate::TerminatedPayload;
+use serde::Serialize;
+use std::time::Duration;
+
+/// A signal sent to a child while shutting it down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ShutdownSignal {
+    /// SIGINT on UNIX. On Windows, CTRL+BREAK to the console process group.
+    Interrupt,
+    /// SIGTERM on UNIX. On Windows, CTRL+BREAK to the console process group.
+    Terminate,
+    /// SIGKILL on UNIX. `TerminateProcess` on Windows.
+    Kill,
+}
+
+/// A step of a [`ShutdownPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownStep {
+    /// Signal to send.
+    pub signal: ShutdownSignal,
+    /// How long to wait for the child to exit before moving to the next step.
+    pub timeout: Duration,
+}
+
+/// Sequence of signals sent to a child by [`crate::CommandChild::terminate`].
+///
+/// The default policy sends SIGINT, then SIGTERM, then SIGKILL:
+///
+/// ```rust
+/// use sidecar::{ShutdownPolicy, ShutdownSignal};
+/// use std::time::Duration;
+///
+/// let policy = ShutdownPolicy::new()
+///     .then(ShutdownSignal::Interrupt, Duration::from_millis(1_500))
+///     .then(ShutdownSignal::Terminate, Duration::from_millis(1_500))
+///     .then(ShutdownSignal::Kill, Duration::from_secs(2));
+/// assert_eq!(policy, ShutdownPolicy::default());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownPolicy {
+    steps: Vec<ShutdownStep>,
+}
+
+impl Default for ShutdownPolicy {
+    fn default() -> Self {
+        Self::new()
+            .then(ShutdownSignal::Interrupt, Duration::from_millis(1_500))
+            .then(ShutdownSignal::Terminate, Duration::from_millis(1_500))
+            .then(ShutdownSignal::Kill, Duration::from_secs(2))
+    }
+}
+
+impl ShutdownPolicy {
+    /// Creates an empty policy. Add steps with [`ShutdownPolicy::then`].
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Appends a step: send `signal`, then wait up to `timeout` for the child to exit.
+    #[must_use]
+    pub fn then(mut self, signal: ShutdownSignal, timeout: Duration) -> Self {
+        self.steps.push(ShutdownStep { signal, timeout });
+        self
+    }
+
+    /// Returns the steps, in order.
+    pub fn steps(&self) -> &[ShutdownStep] {
+        &self.steps
+    }
+
+    /// Returns the longest time the whole policy can take.
+    pub fn total_timeout(&self) -> Duration {
+        self.steps.iter().map(|step| step.timeout).sum()
+    }
+}
+
+/// How a child exited after [`crate::CommandChild::terminate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum Termination {
+    /// The child had already exited.
+    AlreadyExited(TerminatedPayload),
+    /// The child exited after `signal` was sent.
+    Exited {
+        /// Last signal sent before the child exited.
+        signal: ShutdownSignal,
+        /// Exit status.
+        status: TerminatedPayload,
+        /// Time from the first signal until the child was reaped.
+        elapsed: Duration,
+    },
+    /// The child was still running after the last step of the policy.
+    TimedOut {
+        /// Time spent on the whole policy.
+        elapsed: Duration,
+    },
+}
+
+impl Termination {
+    /// Returns whether the child is known to have exited.
+    pub fn has_exited(&self) -> bool {
+        !matches!(self, Self::TimedOut { .. })
+    }
+
+    /// Returns whether the child exited without being forcibly killed.
+    pub fn is_graceful(&self) -> bool {
+        match self {
+            Self::AlreadyExited(_) => true,
+            Self::Exited { signal, .. } => *signal != ShutdownSignal::Kill,
+            Self::TimedOut { .. } => false,
+        }
+    }
+}
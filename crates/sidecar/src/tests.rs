@@ -6,8 +6,15 @@ mod test {
 
     const TIMEOUT_MS: u128 = 5_000;
 
+    /// Serializes tests, as [`kill_children`] affects every child spawned by this process.
+    fn serial() -> std::sync::MutexGuard<'static, ()> {
+        static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     #[test]
     fn test_cmd_output() {
+        let _serial = serial();
         let ws_root = find_workspace_root().expect("failed to find workspace root");
         let cargo_toml = ws_root.join("Cargo.toml");
 
@@ -62,6 +69,7 @@ mod test {
 
     #[test]
     fn test_cmd_fail() {
+        let _serial = serial();
         block_on(async move {
             let mut time_start = tokio::time::Instant::now();
             let mut terminated = false;
@@ -122,6 +130,7 @@ mod test {
 
     #[test]
     fn test_command_interruption() {
+        let _serial = serial();
         #[cfg(windows)]
         let command = Command::new("ping").args(["-t", "127.0.0.1"]);
 
@@ -132,4 +141,436 @@ mod test {
         command.spawn().unwrap();
         kill_children();
     }
+
+    #[test]
+    fn test_backpressure_preserves_order() {
+        let _serial = serial();
+        const LINES: usize = 500;
+
+        block_on(async move {
+            #[cfg(windows)]
+            let cmd =
+                Command::new("powershell").args(["-Command", "1..500 | ForEach-Object { $_ }"]);
+            #[cfg(not(windows))]
+            let cmd = Command::new("sh").args([
+                "-c",
+                "i=1; while [ $i -le 500 ]; do echo $i; i=$((i+1)); done",
+            ]);
+
+            let (mut rx, child) = cmd.buffer_size(1).spawn().expect("failed to spawn command");
+
+            let mut lines = Vec::new();
+            let mut terminated = false;
+            while let Some(event) = rx.recv().await {
+                assert!(!terminated, "no event expected after Terminated");
+                match event {
+                    CommandEvent::Stdout(line) => {
+                        lines.push(line.trim().parse::<usize>().expect("numeric line"));
+                        // A slow consumer makes the readers wait on the full channel.
+                        if lines.len() % 50 == 0 {
+                            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                        }
+                    }
+                    CommandEvent::Terminated(payload) => {
+                        assert_eq!(payload.code, Some(0));
+                        terminated = true;
+                    }
+                    _ => eprintln!("unexpected event: {:?}", event),
+                }
+            }
+
+            assert!(terminated, "expected Terminated event");
+            assert_eq!(lines, (1..=LINES).collect::<Vec<_>>());
+
+            let metrics = child.metrics();
+            assert_eq!(metrics.sent, LINES as u64 + 1);
+            assert_eq!(metrics.dropped, 0);
+            assert!(metrics.backpressure_waits > 0);
+            assert_eq!(metrics.peak_queued, 1);
+        });
+    }
+
+    #[test]
+    fn test_child_kill() {
+        let _serial = serial();
+        block_on(async move {
+            #[cfg(windows)]
+            let cmd = Command::new("ping").args(["-t", "127.0.0.1"]);
+            #[cfg(unix)]
+            // Not `cat`: it exits on its own once stdin is closed by `kill()`.
+            let cmd = Command::new("sleep").args(["30"]);
+
+            let (mut rx, child) = cmd.spawn().expect("failed to spawn command");
+            let pid = child.pid();
+            child.kill().expect("failed to kill child");
+
+            let terminated = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+                while let Some(event) = rx.recv().await {
+                    if let CommandEvent::Terminated(payload) = event {
+                        return Some(payload);
+                    }
+                }
+                None
+            })
+            .await
+            .expect("timed out waiting for the child to exit")
+            .expect("expected Terminated event");
+
+            #[cfg(unix)]
+            assert_eq!(terminated.signal, Some(9));
+            #[cfg(windows)]
+            assert_ne!(terminated.code, Some(0));
+            assert!(!commands().lock().unwrap().contains_key(&pid));
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminate_graceful() {
+        let _serial = serial();
+        block_on(async move {
+            let (_rx, child) = Command::new("sleep")
+                .args(["30"])
+                .spawn()
+                .expect("failed to spawn command");
+
+            let termination = child.terminate().await;
+            let Termination::Exited { signal, status, .. } = &termination else {
+                panic!("unexpected termination: {termination:?}");
+            };
+            assert_eq!(*signal, ShutdownSignal::Interrupt);
+            assert_eq!(status.signal, Some(2));
+            assert!(termination.is_graceful());
+
+            assert!(matches!(
+                child.terminate().await,
+                Termination::AlreadyExited(_)
+            ));
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminate_escalation() {
+        let _serial = serial();
+        block_on(async move {
+            let policy = ShutdownPolicy::new()
+                .then(ShutdownSignal::Interrupt, Duration::from_millis(200))
+                .then(ShutdownSignal::Terminate, Duration::from_millis(200))
+                .then(ShutdownSignal::Kill, Duration::from_secs(2));
+            // Ignored signals are inherited through `exec`.
+            let (_rx, child) = Command::new("sh")
+                .args(["-c", "trap '' INT TERM; exec sleep 30"])
+                .shutdown_policy(policy)
+                .spawn()
+                .expect("failed to spawn command");
+            // Give the shell time to install the traps.
+            tokio::time::sleep(Duration::from_millis(200)).await;
+
+            let termination = child.terminate().await;
+            let Termination::Exited {
+                signal,
+                status,
+                elapsed,
+            } = &termination
+            else {
+                panic!("unexpected termination: {termination:?}");
+            };
+            assert_eq!(*signal, ShutdownSignal::Kill);
+            assert_eq!(status.signal, Some(9));
+            assert!(*elapsed >= Duration::from_millis(400));
+            assert!(!termination.is_graceful());
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminate_timeout() {
+        let _serial = serial();
+        block_on(async move {
+            let policy = ShutdownPolicy::new().then(ShutdownSignal::Terminate, Duration::ZERO);
+            let (_rx, child) = Command::new("sh")
+                .args(["-c", "trap '' TERM; exec sleep 30"])
+                .shutdown_policy(policy)
+                .spawn()
+                .expect("failed to spawn command");
+            tokio::time::sleep(Duration::from_millis(200)).await;
+
+            let termination = child.terminate().await;
+            assert!(matches!(termination, Termination::TimedOut { .. }));
+            assert!(!termination.has_exited());
+
+            child.kill().expect("failed to kill child");
+        });
+    }
+
+    /// Whether `pid` is gone or a zombie.
+    #[cfg(target_os = "linux")]
+    fn is_dead(pid: u32) -> bool {
+        match std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+            // The state follows the parenthesized command name.
+            Ok(stat) => stat
+                .rsplit_once(") ")
+                .is_some_and(|(_, rest)| rest.starts_with('Z')),
+            Err(_) => true,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    async fn wait_dead(pid: u32) -> bool {
+        let start = tokio::time::Instant::now();
+        while start.elapsed().as_millis() < TIMEOUT_MS {
+            if is_dead(pid) {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        false
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_group() {
+        let _serial = serial();
+        block_on(async move {
+            // Non-interactive shells ignore SIGINT in background jobs, so start with SIGTERM.
+            let policy = ShutdownPolicy::new()
+                .then(ShutdownSignal::Terminate, Duration::from_secs(2))
+                .then(ShutdownSignal::Kill, Duration::from_secs(2));
+            let (mut rx, child) = Command::new("sh")
+                .args(["-c", "sleep 30 & echo $!; wait"])
+                .process_group(true)
+                .shutdown_policy(policy)
+                .spawn()
+                .expect("failed to spawn command");
+
+            let pid = nix::unistd::Pid::from_raw(child.pid() as i32);
+            assert_eq!(nix::unistd::getpgid(Some(pid)), Ok(pid));
+
+            let grandchild: u32 = loop {
+                match rx.recv().await.expect("channel closed") {
+                    CommandEvent::Stdout(line) => break line.trim().parse().unwrap(),
+                    CommandEvent::Terminated(_) => panic!("terminated early"),
+                    _ => {}
+                }
+            };
+            assert!(!is_dead(grandchild));
+
+            let termination = child.terminate().await;
+            assert!(termination.is_graceful(), "{termination:?}");
+            assert!(wait_dead(grandchild).await, "grandchild survived");
+        });
+    }
+
+    /// Environment variable that enables [`helper_parent_death`].
+    #[cfg(target_os = "linux")]
+    const HELPER_ENV: &str = "SIDECAR_TEST_HELPER";
+
+    /// Spawns a long-running child, prints its pid and exits without cleaning up.
+    ///
+    /// Run by [`test_kill_on_parent_death`] in a separate process.
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore = "helper process"]
+    fn helper_parent_death() {
+        if std::env::var_os(HELPER_ENV).is_none() {
+            return;
+        }
+        let (_rx, child) = Command::new("sleep")
+            .args(["30"])
+            .kill_on_parent_death(true)
+            .spawn()
+            .expect("failed to spawn command");
+        println!("pid={}", child.pid());
+        std::process::exit(0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_kill_on_parent_death() {
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "tests::test::helper_parent_death",
+                "--exact",
+                "--ignored",
+                "--nocapture",
+            ])
+            .env(HELPER_ENV, "1")
+            .output()
+            .expect("failed to run helper");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let pid: u32 = stdout
+            .lines()
+            .find_map(|line| line.split_once("pid=").map(|(_, pid)| pid.trim()))
+            .unwrap_or_else(|| panic!("helper printed no pid: {stdout}"))
+            .parse()
+            .unwrap();
+
+        let reaped = block_on(wait_dead(pid));
+        if !reaped {
+            nix::sys::signal::kill(
+                nix::unistd::Pid::from_raw(pid as i32),
+                nix::sys::signal::Signal::SIGKILL,
+            )
+            .ok();
+        }
+        assert!(reaped, "child outlived its parent");
+    }
+
+    /// Runs `cmd` to completion and returns its events, except the termination.
+    #[cfg(unix)]
+    fn collect_events(cmd: Command) -> Vec<CommandEvent> {
+        let (mut rx, _child) = cmd.spawn().expect("failed to spawn command");
+        block_on(async move {
+            let mut events = Vec::new();
+            while let Some(event) = rx.recv().await {
+                if !matches!(event, CommandEvent::Terminated(_)) {
+                    events.push(event);
+                }
+            }
+            events
+        })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_lossy() {
+        let _serial = serial();
+        let printf = || Command::new("printf").args([r"ok\n\377bad\n"]);
+
+        let strict = collect_events(printf());
+        assert!(
+            matches!(&strict[..], [CommandEvent::Stdout(ok), CommandEvent::Error(_)] if ok == "ok\n")
+        );
+
+        let lossy = collect_events(printf().output_mode(OutputMode::LossyLines));
+        let lines: Vec<_> = lossy
+            .iter()
+            .map(|event| match event {
+                CommandEvent::Stdout(line) => line.as_str(),
+                _ => panic!("unexpected event: {event:?}"),
+            })
+            .collect();
+        assert_eq!(lines, ["ok\n", "\u{FFFD}bad\n"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_bytes() {
+        let _serial = serial();
+        let events = collect_events(
+            Command::new("printf")
+                .args([r"\377\000ab\ncd"])
+                .output_mode(OutputMode::Bytes),
+        );
+        let mut bytes = Vec::new();
+        for event in events {
+            match event {
+                CommandEvent::StdoutBytes(chunk) => bytes.extend(chunk),
+                _ => panic!("unexpected event: {event:?}"),
+            }
+        }
+        assert_eq!(bytes, b"\xff\x00ab\ncd");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_max_line_length() {
+        let _serial = serial();
+        let stdout = |events: Vec<CommandEvent>| -> Vec<String> {
+            events
+                .into_iter()
+                .map(|event| match event {
+                    CommandEvent::Stdout(line) => line,
+                    _ => panic!("unexpected event: {event:?}"),
+                })
+                .collect()
+        };
+
+        let long = "a".repeat(10_000);
+        let pieces = stdout(collect_events(
+            Command::new("echo").args([&long]).max_line_length(1_000),
+        ));
+        assert_eq!(pieces.len(), 11);
+        assert!(pieces.iter().all(|piece| piece.len() <= 1_000));
+        assert_eq!(pieces.concat(), format!("{long}\n"));
+
+        // A multi-byte character split across two pieces is decoded whole.
+        let pieces = stdout(collect_events(
+            Command::new("echo").args(["héllo"]).max_line_length(2),
+        ));
+        assert!(pieces.len() > 1);
+        assert_eq!(pieces.concat(), "héllo\n");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_resource_limits() {
+        let _serial = serial();
+        let limits = ResourceLimits {
+            open_files: Some(64),
+            cpu_time: Some(100),
+            nice: Some(5),
+            no_new_privileges: true,
+            ..Default::default()
+        };
+        let output = Command::new("sh")
+            .args([
+                "-c",
+                "ulimit -n; ulimit -t; nice; grep NoNewPrivs /proc/self/status",
+            ])
+            .resource_limits(limits)
+            .output()
+            .expect("failed to run command");
+        assert!(output.status.success());
+        let lines: Vec<_> = output.stdout.split_whitespace().collect();
+        assert_eq!(lines, ["64", "100", "5", "NoNewPrivs:", "1"]);
+    }
+
+    /// Spawning with a negative `nice` as a non-root user keeps the current priority.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_unprivileged_negative_nice() {
+        use std::os::unix::{fs::PermissionsExt, process::CommandExt};
+
+        // SAFETY: `geteuid` has no preconditions.
+        if unsafe { nix::libc::geteuid() } == 0 {
+            // Re-run this test as `nobody`, from a copy of the test binary it can access.
+            let dir = std::env::temp_dir().join(format!("sidecar-test-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).expect("failed to create test dir");
+            let exe = dir.join("sidecar-test");
+            std::fs::copy(std::env::current_exe().unwrap(), &exe).unwrap();
+            for path in [&dir, &exe] {
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            }
+            let output = std::process::Command::new(&exe)
+                .args(["tests::test::test_unprivileged_negative_nice", "--exact"])
+                .uid(65534)
+                .gid(65534)
+                .output()
+                .expect("failed to run test as nobody");
+            std::fs::remove_dir_all(&dir).ok();
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            assert!(output.status.success(), "{stdout}");
+            assert!(stdout.contains("1 passed"), "{stdout}");
+            return;
+        }
+
+        let _serial = serial();
+        let niceness = |limits| {
+            let output = Command::new("sh")
+                .args(["-c", "nice"])
+                .resource_limits(limits)
+                .output()
+                .expect("failed to run command");
+            assert!(output.status.success());
+            output.stdout.trim().to_string()
+        };
+        let current = niceness(ResourceLimits::default());
+        let limits = ResourceLimits {
+            nice: Some(-5),
+            ..Default::default()
+        };
+        assert_eq!(niceness(limits), current);
+    }
 }
@@ -0,0 +1,100 @@
+#[cfg(target_os = "linux")]
+use crate::ResourceLimits;
+use crate::ShutdownSignal;
+#[cfg(target_os = "linux")]
+use log::warn;
+use nix::sys::signal::{Signal, kill, killpg};
+use nix::unistd::Pid;
+
+/// Sends `signal` to `pid`, or to its whole process group if `group` is set.
+pub fn send_signal(pid: u32, group: bool, signal: ShutdownSignal) {
+    let signal = match signal {
+        ShutdownSignal::Interrupt => Signal::SIGINT,
+        ShutdownSignal::Terminate => Signal::SIGTERM,
+        ShutdownSignal::Kill => Signal::SIGKILL,
+    };
+    let pid = Pid::from_raw(pid as i32);
+    if group {
+        killpg(pid, signal).ok();
+    } else {
+        kill(pid, signal).ok();
+    }
+}
+
+/// Returns a `pre_exec` hook that makes the kernel send SIGKILL to the child when its parent dies.
+///
+/// The signal fires when the *thread* that spawned the child exits, so children must be
+/// spawned from long-lived threads, like the Tokio runtime workers.
+#[cfg(target_os = "linux")]
+pub fn parent_death_signal() -> impl FnMut() -> std::io::Result<()> + Send + Sync + 'static {
+    use nix::sys::prctl::set_pdeathsig;
+    use nix::unistd::{getpid, getppid};
+
+    let parent = getpid();
+    move || {
+        set_pdeathsig(Signal::SIGKILL)?;
+        // The parent may have died before the signal was set.
+        if getppid() != parent {
+            return Err(std::io::Error::other("parent process exited"));
+        }
+        Ok(())
+    }
+}
+
+/// Warns if `pid` didn't get the `nice` priority, as raising it requires privileges.
+#[cfg(target_os = "linux")]
+pub fn warn_unapplied_priority(pid: u32, nice: i32) {
+    use nix::errno::Errno;
+    use nix::libc;
+
+    Errno::clear();
+    // SAFETY: plain system call.
+    let current = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid) };
+    // -1 is also a valid priority, so errors are told apart through errno.
+    if current == -1 && Errno::last_raw() != 0 {
+        return;
+    }
+    if current != nice {
+        warn!(
+            "sidecar: unable to set the priority of pid {pid} to {nice}, keeping {current}. Raising the priority requires privileges"
+        );
+    }
+}
+
+/// Returns a `pre_exec` hook that applies `limits` to the child.
+#[cfg(target_os = "linux")]
+pub fn resource_limits(
+    limits: ResourceLimits,
+) -> impl FnMut() -> std::io::Result<()> + Send + Sync + 'static {
+    use nix::errno::Errno;
+    use nix::libc;
+    use nix::sys::prctl::set_no_new_privs;
+    use nix::sys::resource::{Resource, getrlimit, setrlimit};
+
+    move || {
+        let rlimits = [
+            (Resource::RLIMIT_AS, limits.address_space),
+            (Resource::RLIMIT_NOFILE, limits.open_files),
+            (Resource::RLIMIT_CPU, limits.cpu_time),
+        ];
+        for (resource, limit) in rlimits {
+            if let Some(limit) = limit {
+                let (_, hard) = getrlimit(resource)?;
+                let limit = limit.min(hard);
+                setrlimit(resource, limit, limit)?;
+            }
+        }
+        if let Some(nice) = limits.nice {
+            // SAFETY: plain system call.
+            match Errno::result(unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) }) {
+                // Keep the current priority. The parent warns, see `warn_unapplied_priority`.
+                Ok(_) | Err(Errno::EPERM | Errno::EACCES) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        if limits.no_new_privileges {
+            set_no_new_privs()?;
+        }
+        Ok(())
+    }
+}
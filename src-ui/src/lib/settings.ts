@@ -1,9 +1,22 @@
 import * as jsonpatch from "fast-json-patch";
 import { toast } from "svelte-sonner";
 import { m } from "./i18n";
-import { setAppConfig } from "./tauri";
+import { onConfigChanged, setAppConfig } from "./tauri";
 import type { Config } from "./types/gen/Config";
 
+/**
+ * Whether a JSON Pointer targets a key set by the system policy.
+ *
+ * `get_config` lists those keys, dotted, in `read_only`.
+ */
+export function isReadOnly(config: Config, pointer: string) {
+    const readOnly = (config as Config & { read_only?: string[] }).read_only ?? [];
+    const key = pointer.slice(1).split("/").join(".");
+    return readOnly.some(
+        (k) => k === key || key.startsWith(`${k}.`) || k.startsWith(`${key}.`)
+    );
+}
+
 /**
  * Generate a configuration patch (RFC 6902) and send it to the Tauri back-end.
  *
@@ -11,7 +24,9 @@ import type { Config } from "./types/gen/Config";
  * updates that require a restart surface a restart notice.
  */
 export async function patchConfig(initial: Config, current: Config) {
-    const diff = jsonpatch.compare(initial, current);
+    const diff = jsonpatch
+        .compare(initial, current)
+        .filter((op) => !isReadOnly(initial, op.path));
 
     if (Object.keys(diff).length === 0) return false;
     if (import.meta.env.DEV) console.log(diff);
@@ -30,3 +45,25 @@ export async function patchConfig(initial: Config, current: Config) {
         return Promise.reject();
     }
 }
+
+/**
+ * Reload a settings section when the configuration changes elsewhere, like in another
+ * window or by editing the configuration file.
+ *
+ * Only changes to keys starting with `prefix` are considered, and unsaved changes are kept.
+ *
+ * @returns a function to stop listening
+ */
+export function followConfigChanges(
+    prefix: string,
+    reload: () => Promise<void>,
+    hasPendingChanges: () => boolean
+) {
+    const unlisten = onConfigChanged(async ({ changes }) => {
+        if (hasPendingChanges()) return;
+        if (changes.some((change) => change.key.startsWith(prefix))) await reload();
+    });
+    return () => {
+        unlisten.then((stop) => stop());
+    };
+}
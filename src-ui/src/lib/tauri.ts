@@ -5,8 +5,15 @@
  */
 
 import { isTauri, invoke as TauriInvoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { open as TauriOpen } from "@tauri-apps/plugin-shell";
+import type { ConfigChange } from "./types/gen/ConfigChange";
+import type { ConfigChangedEvent } from "./types/gen/ConfigChangedEvent";
+import type { ConfigImportResult } from "./types/gen/ConfigImportResult";
+import type { ConfigReloadResult } from "./types/gen/ConfigReloadResult";
 import type { ConfigUpdateResult } from "./types/gen/ConfigUpdateResult";
+import type { Provenance } from "./types/gen/Provenance";
+import type { ResourceSample } from "./types/gen/ResourceSample";
 
 const browserError = new Error("Not running in Tauri!");
 const TAURI = typeof window !== "undefined" && isTauri();
@@ -88,6 +95,15 @@ export async function getAppConfig(): Promise<string> {
     return (await invoke("get_config")) as string;
 }
 
+/**
+ * Get the effective value of each configuration key, and the layer that set it.
+ *
+ * Lets the settings UI explain overridden values, like "set by MEMOSPOT_MEMOS__PORT".
+ */
+export async function getConfigProvenance(): Promise<Provenance[]> {
+    return (await invoke("get_config_provenance")) as Provenance[];
+}
+
 export async function getDefaultAppConfig(): Promise<string> {
     return (await invoke("get_default_config")) as string;
 }
@@ -105,6 +121,75 @@ export async function setAppConfig(JSONPatch: string): Promise<ConfigUpdateResul
     return (await invoke("set_config", { patch: JSONPatch })) as ConfigUpdateResult;
 }
 
+/**
+ * Export a portable configuration document.
+ *
+ * @param json export as JSON instead of YAML
+ * @param stripMachine leave out window geometry, the last update check and absolute paths
+ * @param stripSecrets leave out secrets and environment variables
+ */
+export async function exportAppConfig(
+    json: boolean,
+    stripMachine: boolean,
+    stripSecrets: boolean
+): Promise<string> {
+    return (await invoke("export_config", { json, stripMachine, stripSecrets })) as string;
+}
+
+/**
+ * Import a configuration document exported from another machine.
+ *
+ * Rejects invalid documents. With `dryRun`, the changes are only reported.
+ * @returns the changes, as a JSON Patch, and whether they were applied
+ */
+export async function importAppConfig(
+    document: string,
+    dryRun: boolean
+): Promise<ConfigImportResult> {
+    return (await invoke("import_config", { document, dryRun })) as ConfigImportResult;
+}
+
+/**
+ * List recorded configuration changes, oldest first.
+ */
+export async function getConfigHistory(): Promise<ConfigChange[]> {
+    return (await invoke("get_config_history")) as ConfigChange[];
+}
+
+/**
+ * Revert a recorded configuration change.
+ *
+ * The revert is recorded as a change too, so it can be reverted as well.
+ * @returns the update result, including whether a restart is required
+ */
+export async function revertConfigChange(id: number | bigint): Promise<ConfigUpdateResult> {
+    return (await invoke("revert_config_change", { id: Number(id) })) as ConfigUpdateResult;
+}
+
+/**
+ * List the names of the secrets stored in the vault.
+ */
+export async function listSecrets(): Promise<string[]> {
+    return (await invoke("list_secrets")) as string[];
+}
+
+/**
+ * Store a secret in the vault.
+ *
+ * Environment variable values can reference it as `secret:<name>`.
+ */
+export async function setSecret(name: string, value: string): Promise<void> {
+    await invoke("set_secret", { name, value });
+}
+
+/**
+ * Delete a secret from the vault.
+ * @returns whether the secret existed
+ */
+export async function deleteSecret(name: string): Promise<boolean> {
+    return (await invoke("delete_secret", { name })) as boolean;
+}
+
 /**
  * Check if a path exists.
  *
@@ -114,3 +199,52 @@ export async function setAppConfig(JSONPatch: string): Promise<ConfigUpdateResul
 export async function pathExists(path: string): Promise<boolean> {
     return (await invoke("path_exists", { path: path })) as boolean;
 }
+
+/**
+ * Post a memo to the active Memos server.
+ *
+ * Used by the quick-capture window. Rejects with the server error, if any.
+ * @param content memo content, in Markdown
+ */
+export async function quickCapture(content: string): Promise<void> {
+    await invoke("quick_capture", { content: content });
+}
+
+/**
+ * Get the recent resource usage samples of the managed Memos server, oldest first.
+ *
+ * Empty if the server is remote or monitoring is disabled.
+ */
+export async function getMemosResources(): Promise<ResourceSample[]> {
+    return await invoke("get_memos_resources");
+}
+
+/**
+ * Listen for external changes to the configuration file, after they're merged.
+ *
+ * @param handler called with whether a restart is required, and the replaced in-app changes
+ * @returns a function to stop listening
+ */
+export async function onConfigReloaded(
+    handler: (result: ConfigReloadResult) => void
+): Promise<UnlistenFn> {
+    if (!TAURI) return () => {};
+    return await listen<ConfigReloadResult>("memospot-config-reloaded", (event) =>
+        handler(event.payload)
+    );
+}
+
+/**
+ * Listen for changes to the configuration, made in any window or by editing the file.
+ *
+ * @param handler called with the changed values, and whether a restart is required
+ * @returns a function to stop listening
+ */
+export async function onConfigChanged(
+    handler: (event: ConfigChangedEvent) => void
+): Promise<UnlistenFn> {
+    if (!TAURI) return () => {};
+    return await listen<ConfigChangedEvent>("memospot-config-changed", (event) =>
+        handler(event.payload)
+    );
+}
@@ -2,4 +2,11 @@
 import type { Memos } from "./Memos";
 import type { Memospot } from "./Memospot";
 
-export type Config = { memos: Memos, memospot: Memospot, };
+/**
+ * Memospot configuration file, `memospot.yaml`.
+ */
+export type Config = { 
+/**
+ * Configuration format version, used to migrate files from previous versions.
+ */
+config_version: number | null, memos: Memos, memospot: Memospot, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A recorded configuration change.
+ */
+export type ConfigChange = { 
+/**
+ * Identifier, increasing with each change.
+ */
+id: bigint, 
+/**
+ * When the change was made, in milliseconds since the Unix epoch.
+ */
+timestamp: bigint, 
+/**
+ * The change, as a JSON Patch (RFC 6902).
+ */
+patch: Array<{ op: string, path: string, value?: unknown }>, 
+/**
+ * JSON Patch reverting the change.
+ */
+inverse: Array<{ op: string, path: string, value?: unknown }>, };
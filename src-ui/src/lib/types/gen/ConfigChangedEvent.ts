@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConfigValueChange } from "./ConfigValueChange";
+
+/**
+ * Broadcast after the current configuration changes, from any window or the file.
+ */
+export type ConfigChangedEvent = { 
+/**
+ * Changed values, in key order.
+ */
+changes: Array<ConfigValueChange>, 
+/**
+ * Whether the configuration now differs from the running one in settings that only
+ * take effect after restarting Memospot.
+ */
+restart_required: boolean, };
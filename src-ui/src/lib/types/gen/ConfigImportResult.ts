@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of importing a configuration document.
+ */
+export type ConfigImportResult = { 
+/**
+ * Changes to the current configuration, as a JSON Patch (RFC 6902).
+ */
+patch: Array<{ op: string, path: string, value?: unknown }>, 
+/**
+ * Whether the changes were saved. Dry runs only report them.
+ */
+applied: boolean, 
+/**
+ * Whether the changes only take effect after restarting Memospot.
+ */
+restart_required: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of merging external changes to the configuration file.
+ */
+export type ConfigReloadResult = { 
+/**
+ * Whether some changes only take effect after restarting Memospot.
+ */
+restart_required: boolean, 
+/**
+ * Dotted keys whose unsaved in-app changes were replaced by the file's values.
+ */
+conflicts: Array<string>, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A changed configuration value.
+ */
+export type ConfigValueChange = { 
+/**
+ * Dotted key, like `memos.port`.
+ */
+key: string, 
+/**
+ * JSON Pointer to the value, like `/memos/port`.
+ */
+path: string, 
+/**
+ * Previous value, `null` if it was unset.
+ */
+old: unknown, 
+/**
+ * New value, `null` if it's now unset.
+ */
+new: unknown, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Diagnostics = { 
+/**
+ * Keys whose values are redacted from diagnostics bundles.
+ *
+ * Matches configuration keys and environment variable names containing any of these,
+ * case-insensitively.
+ */
+redact_keys: Array<string> | null, 
+/**
+ * Redact the values of all user-provided environment variables.
+ */
+redact_env_values: boolean | null, };
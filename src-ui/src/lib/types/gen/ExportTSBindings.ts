@@ -1,5 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Config } from "./Config";
+import type { ConfigChange } from "./ConfigChange";
+import type { ConfigChangedEvent } from "./ConfigChangedEvent";
+import type { ConfigImportResult } from "./ConfigImportResult";
+import type { ConfigReloadResult } from "./ConfigReloadResult";
 import type { ConfigUpdateResult } from "./ConfigUpdateResult";
+import type { Provenance } from "./Provenance";
 
-export type ExportTSBindings = { "Config": Config } | { "ConfigUpdateResult": ConfigUpdateResult };
+export type ExportTSBindings = { "Config": Config } | { "ConfigUpdateResult": ConfigUpdateResult } | { "ConfigReloadResult": ConfigReloadResult } | { "ConfigImportResult": ConfigImportResult } | { "ConfigChange": ConfigChange } | { "ConfigChangedEvent": ConfigChangedEvent } | { "Provenance": Provenance };
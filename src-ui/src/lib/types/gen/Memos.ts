@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { EnvironmentVariables } from "./EnvironmentVariables";
+import type { ResourceLimits } from "./ResourceLimits";
 
 /**
  * Memos configuration.
@@ -52,4 +53,8 @@ port: number | null,
 /**
  * Custom environment variables to pass to Memos.
  */
-env: EnvironmentVariables, };
+env: EnvironmentVariables, 
+/**
+ * Resource limits and sandboxing.
+ */
+limits: ResourceLimits, };
@@ -1,8 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Backups } from "./Backups";
+import type { Diagnostics } from "./Diagnostics";
 import type { EnvironmentVariables } from "./EnvironmentVariables";
 import type { Log } from "./Log";
 import type { Migrations } from "./Migrations";
+import type { Monitor } from "./Monitor";
+import type { QuickCapture } from "./QuickCapture";
 import type { RemoteServer } from "./RemoteServer";
 import type { Updater } from "./Updater";
 import type { Window } from "./Window";
@@ -12,6 +15,10 @@ export type Memospot = {
  * Backups settings.
  */
 backups: Backups, 
+/**
+ * Diagnostics bundle settings.
+ */
+diagnostics: Diagnostics, 
 /**
  * Custom system environment variables to pass to Memospot.
  */
@@ -20,6 +27,14 @@ env: EnvironmentVariables,
  * Database migrations settings.
  */
 migrations: Migrations, log: Log, 
+/**
+ * Memos server resource monitoring settings.
+ */
+monitor: Monitor, 
+/**
+ * Quick-capture settings.
+ */
+quick_capture: QuickCapture, 
 /**
  * Remote server settings.
  */
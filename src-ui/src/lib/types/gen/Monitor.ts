@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Monitor = { 
+/**
+ * Sample the Memos server resource usage.
+ */
+enabled: boolean | null, 
+/**
+ * Sampling interval, in seconds.
+ */
+interval: bigint | null, 
+/**
+ * Log a warning when CPU usage exceeds this percentage. 100% is one full core.
+ */
+warn_cpu_percent: number | null, 
+/**
+ * Log a warning when resident memory exceeds this size, in MiB.
+ */
+warn_memory_mb: bigint | null, 
+/**
+ * Log a warning when the number of open files exceeds this.
+ */
+warn_open_files: bigint | null, 
+/**
+ * Log a warning when the database WAL file exceeds this size, in MiB.
+ */
+warn_wal_mb: bigint | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Configuration layer a value comes from.
+ */
+export type Origin = { "kind": "default" } | { "kind": "system_defaults", path: string, } | { "kind": "file", path: string, } | { "kind": "env", var: string, } | { "kind": "policy", path: string, };
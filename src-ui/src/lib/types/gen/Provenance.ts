@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Origin } from "./Origin";
+
+/**
+ * Effective value of a configuration key, and where it comes from.
+ */
+export type Provenance = { 
+/**
+ * Dotted key, like `memos.port`.
+ */
+key: string, value: unknown, origin: Origin, 
+/**
+ * Profile the value was selected from.
+ */
+profile: string, };
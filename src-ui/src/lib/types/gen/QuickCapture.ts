@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type QuickCapture = { 
+/**
+ * Enable the global quick-capture hotkey.
+ */
+enabled: boolean | null, 
+/**
+ * OS-global hotkey that opens the quick-capture window, like `CmdOrCtrl+Shift+M`.
+ */
+shortcut: string | null, 
+/**
+ * Memos access token used to create memos.
+ *
+ * Store it in the secrets vault and reference it as `secret:<name>` to keep it out of
+ * this file.
+ */
+access_token: string | null, 
+/**
+ * Visibility of captured memos: `PRIVATE`, `PROTECTED` or `PUBLIC`.
+ */
+visibility: string | null, };
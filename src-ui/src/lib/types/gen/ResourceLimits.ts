@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Resource limits for the Memos server.
+ *
+ * Linux only. Ignored on other platforms.
+ */
+export type ResourceLimits = { enabled: boolean | null, 
+/**
+ * Maximum virtual memory, in MiB.
+ */
+max_memory_mb: bigint | null, 
+/**
+ * Maximum number of open files.
+ */
+max_open_files: bigint | null, 
+/**
+ * Maximum CPU time, in seconds. The server is killed once exceeded.
+ */
+max_cpu_seconds: bigint | null, 
+/**
+ * Scheduling priority, from -20 (highest) to 19 (lowest).
+ *
+ * Raising the priority requires privileges. Without them, it's left unchanged.
+ */
+nice: number | null, 
+/**
+ * Prevent the server from gaining privileges through setuid binaries.
+ */
+no_new_privileges: boolean | null, };
@@ -0,0 +1,38 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Resource usage of the Memos server at a point in time.
+ */
+export type ResourceSample = { 
+/**
+ * Sample time, in milliseconds since the Unix epoch.
+ */
+timestamp: bigint, 
+/**
+ * Server process id.
+ */
+pid: number, 
+/**
+ * CPU usage since the previous sample, in percent. 100% is one full core.
+ */
+cpu_percent: number, 
+/**
+ * Resident memory, in bytes.
+ */
+memory: bigint, 
+/**
+ * Open file descriptors, if available on this platform.
+ */
+open_files: bigint | null, 
+/**
+ * Database file size, in bytes.
+ */
+db_size: bigint, 
+/**
+ * Database WAL file size, in bytes.
+ */
+wal_size: bigint, 
+/**
+ * Server uptime, in seconds.
+ */
+uptime: bigint, };
@@ -48,4 +48,14 @@ reduce_animation: boolean | null,
 /**
  * Locale.
  */
-locale: string | null, };
+locale: string | null, 
+/**
+ * Show an icon in the system tray.
+ */
+tray_icon: boolean | null, 
+/**
+ * Keep running in the system tray when the main window is closed.
+ *
+ * Shows the tray icon regardless of `tray_icon`.
+ */
+close_to_tray: boolean | null, };